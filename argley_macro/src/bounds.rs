@@ -0,0 +1,59 @@
+use std::collections::HashSet;
+
+use proc_macro2::{Ident, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{parse_quote, GenericParam, Generics, Type, WherePredicate};
+
+/// Add `FieldType: #bound` predicates, e.g. `FieldType: Arg`, for field types that use any of
+/// the container's type parameters. `PhantomData` fields don't get bounds.
+pub fn add_field_bounds<'a>(
+    generics: &mut Generics,
+    types: impl IntoIterator<Item = &'a Type>,
+    bound: &TokenStream,
+) {
+    let params = generics
+        .params
+        .iter()
+        .filter_map(move |param| match param {
+            GenericParam::Type(param) => Some(param.ident.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if params.is_empty() {
+        return;
+    }
+
+    let mut seen = HashSet::new();
+    let predicates = types
+        .into_iter()
+        .filter(move |ty| !is_phantom_data(ty) && uses_params(ty.to_token_stream(), &params))
+        .filter(move |ty| seen.insert(ty.to_token_stream().to_string()))
+        .map(move |ty| -> WherePredicate {
+            parse_quote! { #ty: #bound }
+        })
+        .collect::<Vec<_>>();
+
+    if !predicates.is_empty() {
+        generics.make_where_clause().predicates.extend(predicates);
+    }
+}
+
+fn is_phantom_data(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map_or(false, move |seg| seg.ident == "PhantomData"),
+        _ => false,
+    }
+}
+
+fn uses_params(tokens: TokenStream, params: &[Ident]) -> bool {
+    tokens.into_iter().any(move |tt| match tt {
+        TokenTree::Ident(ref ident) => params.contains(ident),
+        TokenTree::Group(group) => uses_params(group.stream(), params),
+        _ => false,
+    })
+}
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Field, Fields, GenericArgument, PathArguments, Type};
+
+use crate::container_opts::ContainerOpts;
+
+/// Generate the `ArgBuilder` derive's builder struct & its `impl`s
+pub fn derive(input: DeriveInput) -> syn::Result<TokenStream> {
+    let DeriveInput {
+        attrs,
+        vis,
+        ident: struct_name,
+        generics,
+        data,
+    } = input;
+
+    let fields = match data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => fields.named.iter().collect::<Vec<_>>(),
+            ref other => {
+                return Err(syn::Error::new(
+                    other.span(),
+                    "`ArgBuilder` only supports structs with named fields",
+                ));
+            }
+        },
+        Data::Enum(ref data) => {
+            return Err(syn::Error::new(
+                data.enum_token.span(),
+                "`ArgBuilder` only supports structs",
+            ));
+        }
+        Data::Union(ref data) => {
+            return Err(syn::Error::new(
+                data.union_token.span(),
+                "`ArgBuilder` only supports structs",
+            ));
+        }
+    };
+
+    let opts = ContainerOpts::try_from(attrs)?;
+    check_method_names(&fields)?;
+
+    let builder_name = format_ident!("{struct_name}Builder");
+    let (g1, g2, g3) = generics.split_for_impl();
+
+    let setters = fields.iter().map(move |field| {
+        let field_vis = &field.vis;
+        let ident = field.ident.as_ref().expect("named field");
+        let docs = field.attrs.iter().filter(move |a| a.path().is_ident("doc"));
+
+        if let Some(inner) = option_inner(&field.ty) {
+            let clear = clear_ident(ident);
+            let clear_doc = format!("Reset `{}` to `None`", ident.unraw());
+
+            quote! {
+                #(#docs)*
+                #[must_use]
+                #field_vis fn #ident(mut self, #ident: impl ::std::convert::Into<#inner>) -> Self {
+                    self.0.#ident = ::std::option::Option::Some(::std::convert::Into::into(#ident));
+                    self
+                }
+
+                #[doc = #clear_doc]
+                #[must_use]
+                #field_vis fn #clear(mut self) -> Self {
+                    self.0.#ident = ::std::option::Option::None;
+                    self
+                }
+            }
+        } else {
+            let ty = &field.ty;
+            quote! {
+                #(#docs)*
+                #[must_use]
+                #field_vis fn #ident(mut self, #ident: impl ::std::convert::Into<#ty>) -> Self {
+                    self.0.#ident = ::std::convert::Into::into(#ident);
+                    self
+                }
+            }
+        }
+    });
+
+    let krate = match opts.krate {
+        Some(ref krate) => krate.to_token_stream(),
+        None => quote! { ::argley },
+    };
+    let builder_doc = format!("Builder for [`{struct_name}`], starting from its `Default` value");
+
+    Ok(quote! {
+        #[doc = #builder_doc]
+        #[must_use]
+        #vis struct #builder_name #g1 (#struct_name #g2) #g3;
+
+        const _: () = {
+            use #krate as __argley;
+
+            #[automatically_derived]
+            impl #g1 #struct_name #g2 #g3 {
+                #[doc = #builder_doc]
+                #vis fn builder() -> #builder_name #g2 where Self: ::std::default::Default {
+                    #builder_name(::std::default::Default::default())
+                }
+            }
+
+            #[automatically_derived]
+            impl #g1 #builder_name #g2 #g3 {
+                #(#setters)*
+
+                /// Finish building the struct
+                #[must_use]
+                #vis fn build(self) -> #struct_name #g2 {
+                    self.0
+                }
+            }
+
+            #[automatically_derived]
+            impl #g1 __argley::ArgSetBuilder for #builder_name #g2 #g3 {
+                type Output = #struct_name #g2;
+
+                #[inline]
+                fn build(self) -> Self::Output {
+                    self.0
+                }
+            }
+
+            #[automatically_derived]
+            impl #g1 ::std::convert::From<#builder_name #g2> for #struct_name #g2 #g3 {
+                #[inline]
+                fn from(builder: #builder_name #g2) -> Self {
+                    builder.0
+                }
+            }
+        };
+    })
+}
+
+/// Methods of the builder that setters would shadow
+const BUILDER_METHODS: [&str; 3] = ["build", "add_arg_set", "try_add_arg_set"];
+
+/// Name of the method resetting an `Option` field
+fn clear_ident(ident: &Ident) -> Ident {
+    format_ident!("clear_{}", ident.unraw())
+}
+
+/// Error on setters clashing with the builder's own methods or another field's `clear_` method
+fn check_method_names(fields: &[&Field]) -> syn::Result<()> {
+    let clear_methods = fields
+        .iter()
+        .filter(move |field| option_inner(&field.ty).is_some())
+        .filter_map(move |field| field.ident.as_ref())
+        .map(move |ident| (clear_ident(ident).to_string(), ident))
+        .collect::<HashMap<_, _>>();
+
+    for ident in fields.iter().filter_map(move |field| field.ident.as_ref()) {
+        let name = ident.unraw().to_string();
+        if BUILDER_METHODS.contains(&name.as_str()) {
+            return Err(syn::Error::new(
+                ident.span(),
+                format!("Field `{name}` clashes with the builder's `{name}` method"),
+            ));
+        }
+        if let Some(option) = clear_methods.get(&name) {
+            return Err(syn::Error::new(
+                ident.span(),
+                format!(
+                    "Field `{name}` clashes with the `{name}` method of the `Option` field `{}`",
+                    option.unraw()
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// `T` if the type is an `Option<T>`
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let path = match ty {
+        Type::Path(path) if path.qself.is_none() => &path.path,
+        _ => return None,
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    match segment.arguments {
+        PathArguments::AngleBracketed(ref args) if args.args.len() == 1 => match args.args[0] {
+            GenericArgument::Type(ref inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
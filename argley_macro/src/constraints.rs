@@ -0,0 +1,169 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{quote, ToTokens, TokenStreamExt};
+use syn::LitStr;
+
+use crate::container_opts::ContainerOpts;
+use crate::struct_field::StructField;
+use crate::{new_ident, ARG_CONSUMER};
+
+/// Cross-field constraints of a struct, checked against whether each field got emitted
+#[derive(Default)]
+pub struct Constraints(Vec<Constraint>);
+
+enum Constraint {
+    Conflict(String, String),
+    Requires(String, String),
+    RequiredOneOf(Vec<String>),
+}
+
+impl Constraints {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn collect(fields: &[StructField], opts: &ContainerOpts) -> syn::Result<Self> {
+        let resolve = move |lit: &LitStr| -> syn::Result<String> {
+            let value = lit.value();
+            if fields.iter().any(|f| f.label == value) {
+                Ok(value)
+            } else {
+                Err(syn::Error::new(
+                    lit.span(),
+                    format!("Unknown field `{value}`. Skipped fields can't be referenced."),
+                ))
+            }
+        };
+
+        let mut out = Vec::new();
+        let mut groups: Vec<(String, Vec<String>, bool)> = Vec::new();
+
+        for field in fields {
+            for other in &field.opts.conflicts_with {
+                out.push(Constraint::Conflict(field.label.clone(), resolve(other)?));
+            }
+            for other in &field.opts.requires {
+                out.push(Constraint::Requires(field.label.clone(), resolve(other)?));
+            }
+
+            match (&field.opts.group, &field.opts.required) {
+                (Some(group), required) => {
+                    let name = group.value();
+                    let required = required.is_some();
+
+                    if let Some(entry) = groups.iter_mut().find(|g| g.0 == name) {
+                        entry.1.push(field.label.clone());
+                        entry.2 |= required;
+                    } else {
+                        groups.push((name, vec![field.label.clone()], required));
+                    }
+                }
+                (None, Some(_)) => {
+                    out.push(Constraint::RequiredOneOf(vec![field.label.clone()]));
+                }
+                (None, None) => {}
+            }
+        }
+
+        for (_, members, required) in groups {
+            if required {
+                out.push(Constraint::RequiredOneOf(members));
+            }
+        }
+
+        for list in &opts.required_one_of {
+            let members = list.iter().map(resolve).collect::<syn::Result<Vec<_>>>()?;
+            out.push(Constraint::RequiredOneOf(members));
+        }
+
+        Ok(Self(out))
+    }
+}
+
+impl Constraints {
+    /// Variable to store whether the field with the given label got emitted in, if any of the
+    /// constraints reference it
+    pub fn flag(&self, label: &str) -> Option<Ident> {
+        if self.references(label) {
+            Some(emitted_flag(label))
+        } else {
+            None
+        }
+    }
+
+    /// Make `body`, which emits the fields, storing their [flags](Self::flag), & checks the
+    /// constraints, add its arguments to a buffer first so that nothing gets added if a
+    /// constraint fails. Each field still gets emitted only once.
+    pub fn buffered(body: TokenStream) -> TokenStream {
+        let consumer = new_ident(ARG_CONSUMER);
+        let buffer = new_ident("__argley_buffer");
+
+        quote! {
+            let mut #buffer = __argley::ArgBuffer::default();
+            let __argley_result = {
+                let #consumer = &mut #buffer;
+                #body
+            };
+            #buffer.replay(#consumer);
+            __argley_result
+        }
+    }
+
+    fn references(&self, label: &str) -> bool {
+        self.0.iter().any(move |constraint| match constraint {
+            Constraint::Conflict(a, b) | Constraint::Requires(a, b) => a == label || b == label,
+            Constraint::RequiredOneOf(members) => members.iter().any(move |m| m == label),
+        })
+    }
+}
+
+/// Name of the variable holding whether the field with the given label got emitted
+fn emitted_flag(label: &str) -> Ident {
+    Ident::new(&format!("__argley_emitted_{label}"), Span::call_site())
+}
+
+impl ToTokens for Constraints {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        for constraint in &self.0 {
+            tokens.append_all(match constraint {
+                Constraint::Conflict(a, b) => {
+                    let (flag_a, flag_b) = (emitted_flag(a), emitted_flag(b));
+                    quote! {
+                        if #flag_a && #flag_b {
+                            return ::std::result::Result::Err(__argley::ArgError::new(
+                                __argley::ArgErrorKind::Constraint(__argley::ConstraintViolation::Conflict {
+                                    field: #a,
+                                    other: #b,
+                                })
+                            ));
+                        }
+                    }
+                }
+                Constraint::Requires(a, b) => {
+                    let (flag_a, flag_b) = (emitted_flag(a), emitted_flag(b));
+                    quote! {
+                        if #flag_a && !#flag_b {
+                            return ::std::result::Result::Err(__argley::ArgError::new(
+                                __argley::ArgErrorKind::Constraint(__argley::ConstraintViolation::Requires {
+                                    field: #a,
+                                    required: #b,
+                                })
+                            ));
+                        }
+                    }
+                }
+                Constraint::RequiredOneOf(members) => {
+                    let flags = members.iter().map(|m| emitted_flag(m));
+                    quote! {
+                        if !(#(#flags)||*) {
+                            return ::std::result::Result::Err(__argley::ArgError::new(
+                                __argley::ArgErrorKind::Constraint(__argley::ConstraintViolation::Required {
+                                    one_of: &[#(#members),*],
+                                })
+                            ));
+                        }
+                    }
+                }
+            });
+        }
+    }
+}
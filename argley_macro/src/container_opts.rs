@@ -1,21 +1,59 @@
-use proc_macro2::Ident;
+use proc_macro2::{Ident, Span};
+use syn::parse::ParseStream;
+use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::{Attribute, Expr, Lit, Token};
+use syn::{bracketed, Attribute, Expr, ExprArray, Lit, LitStr, Path, Token, WherePredicate};
 
-use crate::{TryCollectStable, ATTR};
+use crate::rename_rule::RenameRule;
+use crate::{parse_eq, unknown_option, TryCollectStable, ATTR};
 
 #[derive(Default)]
 pub struct ContainerOpts {
     pub drop_name: bool,
     pub as_repr: Option<Ident>,
     pub to_string: bool,
-    pub static_args: Option<syn::ExprArray>,
+    pub static_args: Option<ExprArray>,
+    pub fallible: bool,
+    pub required_one_of: Vec<Vec<LitStr>>,
+    pub value_enum: Option<Ident>,
+    pub subcommand: Option<Ident>,
+    pub flag_enum: Option<Ident>,
+    pub short: bool,
+    pub rename_all: Option<RenameRule>,
+    pub krate: Option<Path>,
+    pub bound: Option<Punctuated<WherePredicate, Token![,]>>,
+    pub schema: bool,
 }
 
 impl ContainerOpts {
+    const OPTIONS: [&'static str; 14] = [
+        "to_string",
+        "drop_name",
+        "fallible",
+        "required_one_of",
+        "as_repr",
+        "value_enum",
+        "subcommand",
+        "flag_enum",
+        "short",
+        "rename_all",
+        "static_args",
+        "bound",
+        "crate",
+        "schema",
+    ];
+
     pub fn should_collect_enum_fields(&self) -> bool {
         !self.to_string && self.as_repr.is_none()
     }
+
+    /// The `value_enum`, `subcommand` or `flag_enum` option, whichever is set
+    pub fn variant_naming(&self) -> Option<&Ident> {
+        self.value_enum
+            .as_ref()
+            .or(self.subcommand.as_ref())
+            .or(self.flag_enum.as_ref())
+    }
 }
 
 impl TryFrom<Vec<Attribute>> for ContainerOpts {
@@ -42,6 +80,65 @@ impl TryFrom<Vec<Attribute>> for ContainerOpts {
             .into_iter()
             .collect::<Self>();
 
+        if (attrs.fallible || !attrs.required_one_of.is_empty())
+            && !attrs.should_collect_enum_fields()
+        {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`fallible` & `required_one_of` can't be combined with `to_string` or `as_repr`",
+            ));
+        }
+
+        if attrs.bound.is_some() && !attrs.should_collect_enum_fields() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`bound` can't be combined with `to_string` or `as_repr`",
+            ));
+        }
+
+        let naming = {
+            let mut naming = [&attrs.value_enum, &attrs.subcommand, &attrs.flag_enum]
+                .into_iter()
+                .flatten();
+            (naming.next(), naming.next())
+        };
+        match naming {
+            (Some(first), Some(second)) => {
+                return Err(syn::Error::new(
+                    second.span(),
+                    format!("`{second}` can't be combined with `{first}`"),
+                ));
+            }
+            (Some(ident), None) => {
+                if !attrs.should_collect_enum_fields() {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("`{ident}` can't be combined with `to_string` or `as_repr`"),
+                    ));
+                }
+            }
+            (None, _) => {
+                if attrs.rename_all.is_some() {
+                    return Err(syn::Error::new(
+                        Span::call_site(),
+                        "`rename_all` requires `value_enum`, `subcommand` or `flag_enum`",
+                    ));
+                }
+            }
+        }
+
+        if attrs.short && attrs.flag_enum.is_none() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "Container-level `short` requires `flag_enum`",
+            ));
+        }
+
+        // Subcommands & flags are identified by their variant name, not the name of the field
+        if attrs.subcommand.is_some() || attrs.flag_enum.is_some() {
+            attrs.drop_name = true;
+        }
+
         if let Some(as_repr) = &attrs.as_repr {
             match repr {
                 None => return Err(syn::Error::new(as_repr.span(), "missing repr")),
@@ -63,52 +160,90 @@ impl TryFrom<Attribute> for ContainerOpts {
         let mut opts = Self::default();
 
         attr.parse_nested_meta(|meta| {
-            let error_span = if let Some(path) = meta.path.get_ident() {
-                match path.to_string().as_str() {
-                    "to_string" => {
-                        opts.to_string = true;
-                        return Ok(());
-                    }
-                    "drop_name" => {
-                        opts.drop_name = true;
-                        return Ok(());
-                    }
-                    "as_repr" => {
-                        opts.as_repr = Some(path.clone());
-                        return Ok(());
-                    }
-                    "static_args" => {
-                        meta.input.parse::<Token![=]>()?;
-                        let static_args = meta.input.parse::<syn::ExprArray>()?;
-                        for el in &static_args.elems {
-                            let span = match el {
-                                Expr::Lit(ref lit) => match lit.lit {
-                                    Lit::Str(_) => continue,
-                                    ref other => other.span(),
-                                },
-                                other => other.span(),
-                            };
-
-                            return Err(syn::Error::new(span, "expected string literal"));
-                        }
-
-                        opts.static_args = Some(static_args);
-
-                        return Ok(());
+            let path = match meta.path.get_ident() {
+                Some(path) => path,
+                None => return Err(syn::Error::new(meta.path.span(), "Expected `Ident`")),
+            };
+
+            match path.to_string().as_str() {
+                "to_string" => {
+                    opts.to_string = true;
+                }
+                "drop_name" => {
+                    opts.drop_name = true;
+                }
+                "fallible" => {
+                    opts.fallible = true;
+                }
+                "required_one_of" => {
+                    meta.input.parse::<Token![=]>()?;
+                    let content;
+                    bracketed!(content in meta.input);
+                    let fields = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                    if fields.is_empty() {
+                        return Err(meta.error("Expected at least one field"));
                     }
-                    _ => path.span(),
+                    opts.required_one_of.push(fields.into_iter().collect());
                 }
-            } else {
-                meta.path.span()
+                "as_repr" => {
+                    opts.as_repr = Some(path.clone());
+                }
+                "value_enum" => {
+                    opts.value_enum = Some(path.clone());
+                }
+                "subcommand" => {
+                    opts.subcommand = Some(path.clone());
+                }
+                "flag_enum" => {
+                    opts.flag_enum = Some(path.clone());
+                }
+                "short" => {
+                    opts.short = true;
+                }
+                "rename_all" => {
+                    opts.rename_all = Some(parse_eq(meta.input)?);
+                }
+                "static_args" => {
+                    opts.static_args = Some(parse_static_args(meta.input)?);
+                }
+                "bound" => {
+                    let bound = parse_eq::<LitStr>(meta.input)?;
+                    opts.bound = Some(bound.parse_with(Punctuated::parse_terminated)?);
+                }
+                "crate" => {
+                    opts.krate = Some(parse_eq::<LitStr>(meta.input)?.parse()?);
+                }
+                "schema" => {
+                    opts.schema = true;
+                }
+                _ => return Err(unknown_option(path, &Self::OPTIONS)),
             };
 
-            Err(syn::Error::new(error_span, "unknown option"))
+            Ok(())
         })?;
 
         Ok(opts)
     }
 }
 
+/// Parse `= ["--arg1", "value1"]`, erroring on anything other than string literals
+pub fn parse_static_args(input: ParseStream) -> syn::Result<ExprArray> {
+    let static_args = parse_eq::<ExprArray>(input)?;
+    for el in &static_args.elems {
+        let span = match el {
+            Expr::Lit(ref lit) => match lit.lit {
+                Lit::Str(_) => continue,
+                ref other => other.span(),
+            },
+            other => other.span(),
+        };
+
+        return Err(syn::Error::new(span, "expected string literal"));
+    }
+
+    Ok(static_args)
+}
+
 impl FromIterator<ContainerOpts> for ContainerOpts {
     fn from_iter<T: IntoIterator<Item = ContainerOpts>>(iter: T) -> Self {
         iter.into_iter()
@@ -119,9 +254,41 @@ impl FromIterator<ContainerOpts> for ContainerOpts {
                 if opts.to_string {
                     acc.to_string = true;
                 }
+                if opts.fallible {
+                    acc.fallible = true;
+                }
+                acc.required_one_of.extend(opts.required_one_of);
                 if opts.as_repr.is_some() {
                     acc.as_repr = opts.as_repr;
                 }
+                if opts.value_enum.is_some() {
+                    acc.value_enum = opts.value_enum;
+                }
+                if opts.subcommand.is_some() {
+                    acc.subcommand = opts.subcommand;
+                }
+                if opts.flag_enum.is_some() {
+                    acc.flag_enum = opts.flag_enum;
+                }
+                if opts.short {
+                    acc.short = true;
+                }
+                if opts.rename_all.is_some() {
+                    acc.rename_all = opts.rename_all;
+                }
+                if let Some(add_bound) = opts.bound {
+                    if let Some(ref mut bound) = acc.bound {
+                        bound.extend(add_bound);
+                    } else {
+                        acc.bound = Some(add_bound);
+                    }
+                }
+                if opts.krate.is_some() {
+                    acc.krate = opts.krate;
+                }
+                if opts.schema {
+                    acc.schema = true;
+                }
 
                 if let Some(add_args) = opts.static_args {
                     if let Some(ref mut static_args) = acc.static_args {
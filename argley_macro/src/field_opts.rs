@@ -1,31 +1,379 @@
 use std::cmp::Ordering;
 use std::iter::Sum;
 
-use crate::{parse_eq, OPT_SKIP};
-use proc_macro2::{Ident, Literal};
+use crate::path_mode::PathMode;
+use crate::{parse_eq, unknown_option, OPT_SKIP};
+use proc_macro2::{Ident, Literal, Span, TokenStream};
+use quote::{quote, quote_spanned, ToTokens};
 use syn::spanned::Spanned;
-use syn::{Attribute, ExprPath};
+use syn::{
+    Attribute, Expr, ExprArray, ExprLit, GenericArgument, Lit, LitBool, LitChar, LitInt, LitStr,
+    Path, PathArguments, Type,
+};
 
 #[derive(Default)]
 pub struct FieldOpts {
     pub skip: bool,
-    pub short: bool,
+    pub short: Option<Ident>,
     pub variadic: Option<Ident>,
     pub position: Option<u16>,
+
+    /// Span of the `position` literal, or of the field itself for implicitly positional tuple
+    /// fields
+    pub position_span: Option<Span>,
     pub rename: Option<Literal>,
-    pub formatter: Option<ExprPath>,
+    pub formatter: Option<Expr>,
+    pub with: Option<Path>,
+    pub precision: Option<LitInt>,
+    pub radix: Option<LitInt>,
+    pub prefix: Option<LitStr>,
+    pub width: Option<LitInt>,
+    pub pad: Option<LitChar>,
+    pub kv_separator: Option<LitStr>,
+    pub kv_join: Option<LitStr>,
+    pub kv_repeat_name: Option<LitBool>,
+    pub path: Option<PathMode>,
+    pub range: Option<Expr>,
+    pub non_empty: Option<Ident>,
+    pub one_of: Option<ExprArray>,
+    pub max_len: Option<LitInt>,
+    pub exists: Option<Ident>,
+    pub conflicts_with: Vec<LitStr>,
+    pub requires: Vec<LitStr>,
+    pub group: Option<LitStr>,
+    pub required: Option<Ident>,
+    pub global: Option<Ident>,
+    pub subcommand: Option<Ident>,
+    pub value_name: Option<LitStr>,
+
+    /// `append` or `replace`; how the `Merge` derive combines collections
+    pub merge: Option<Ident>,
 }
 
 impl FieldOpts {
+    const OPTIONS: [&'static str; 29] = [
+        OPT_SKIP,
+        "short",
+        "variadic",
+        "position",
+        "formatter",
+        "with",
+        "rename",
+        "precision",
+        "radix",
+        "prefix",
+        "width",
+        "pad",
+        "kv_separator",
+        "kv_join",
+        "kv_repeat_name",
+        "path",
+        "range",
+        "non_empty",
+        "one_of",
+        "max_len",
+        "exists",
+        "conflicts_with",
+        "requires",
+        "group",
+        "required",
+        "global",
+        "subcommand",
+        "value_name",
+        "merge",
+    ];
+
     pub fn is_default_field_name(&self) -> bool {
         self.position.is_none() && self.rename.is_none() && self.variadic.is_none()
     }
 
+    /// Span of the first option that can only be emitted through `TryArg`, if any are set
+    pub fn fallible_span(&self) -> Option<Span> {
+        self.path
+            .as_ref()
+            .map(PathMode::span)
+            .or_else(|| self.range.as_ref().map(Spanned::span))
+            .or_else(|| self.non_empty.as_ref().map(Ident::span))
+            .or_else(|| self.one_of.as_ref().map(Spanned::span))
+            .or_else(|| self.max_len.as_ref().map(LitInt::span))
+            .or_else(|| self.exists.as_ref().map(Ident::span))
+            .or_else(|| self.conflicts_with.first().map(LitStr::span))
+            .or_else(|| self.requires.first().map(LitStr::span))
+            .or_else(|| self.group.as_ref().map(LitStr::span))
+            .or_else(|| self.required.as_ref().map(Ident::span))
+    }
+
+    pub fn has_constraints(&self) -> bool {
+        !self.conflicts_with.is_empty()
+            || !self.requires.is_empty()
+            || self.group.is_some()
+            || self.required.is_some()
+    }
+
+    /// `Result<(), ArgError>` expressions validating the field
+    pub fn validators(&self, field_expr: &TokenStream) -> Vec<TokenStream> {
+        let mut out = Vec::new();
+
+        if let Some(ref non_empty) = self.non_empty {
+            out.push(
+                quote_spanned! { non_empty.span()=> __argley::validate::non_empty(#field_expr) },
+            );
+        }
+        if let Some(ref max_len) = self.max_len {
+            out.push(quote! { __argley::validate::max_len(#field_expr, #max_len) });
+        }
+        if let Some(ref range) = self.range {
+            out.push(quote! { __argley::validate::range(#field_expr, #range) });
+        }
+        if let Some(ref one_of) = self.one_of {
+            let value = Ident::new("value", Span::call_site());
+            let mut description = String::from("[");
+            let mut checks = Vec::with_capacity(one_of.elems.len());
+
+            for (idx, el) in one_of.elems.iter().enumerate() {
+                if idx != 0 {
+                    description.push_str(", ");
+                }
+                description.push_str(&el.to_token_stream().to_string());
+
+                checks.push(match *el {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(ref lit),
+                        ..
+                    }) => quote! { #value == #lit },
+                    ref other => quote! { *#value == #other },
+                });
+            }
+            description.push(']');
+
+            out.push(quote! {
+                __argley::validate::one_of(#field_expr, #description, |#value| #(#checks)||*)
+            });
+        }
+        if let Some(ref exists) = self.exists {
+            out.push(quote_spanned! { exists.span()=> __argley::validate::exists(#field_expr) });
+        }
+
+        out
+    }
+
+    /// Expression calling the formatter, if set, with the given field reference
+    pub fn formatter_call(&self, field_expr: &TokenStream) -> Option<TokenStream> {
+        Some(match self.formatter.as_ref()? {
+            // Go through a function so the closure's parameter type gets inferred
+            Expr::Closure(closure) => quote! {
+                {
+                    #[inline(always)]
+                    fn __argley_apply_formatter<'a, T: ?Sized, R>(value: &'a T, formatter: impl FnOnce(&'a T) -> R) -> R {
+                        formatter(value)
+                    }
+                    __argley_apply_formatter(#field_expr, #closure)
+                }
+            },
+            fmt => quote! { #fmt(#field_expr) },
+        })
+    }
+
+    /// Error on `short` or `rename` being set on a field that doesn't get output with a name
+    pub fn check_unnamed_conflicts(&self) -> syn::Result<()> {
+        let kind = if self.variadic.is_some() {
+            "variadic"
+        } else if self.subcommand.is_some() {
+            "subcommand"
+        } else if self.position.is_some() {
+            "positional"
+        } else {
+            return Ok(());
+        };
+
+        if let Some(ref short) = self.short {
+            return Err(syn::Error::new(
+                short.span(),
+                format!("`short` has no effect on {kind} fields"),
+            ));
+        }
+        if let Some(ref rename) = self.rename {
+            return Err(syn::Error::new(
+                rename.span(),
+                format!("`rename` has no effect on {kind} fields"),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Span of the first option whose output can't be parsed back into the field, if any
+    pub fn irreversible_span(&self) -> Option<Span> {
+        self.formatter
+            .as_ref()
+            .map(Spanned::span)
+            .or_else(|| self.with.as_ref().map(Spanned::span))
+            .or_else(|| self.precision.as_ref().map(LitInt::span))
+            .or_else(|| self.radix.as_ref().map(LitInt::span))
+            .or_else(|| self.width.as_ref().map(LitInt::span))
+            .or_else(|| self.prefix.as_ref().map(LitStr::span))
+            .or_else(|| self.pad.as_ref().map(LitChar::span))
+            .or_else(|| self.map_format_span())
+            .or_else(|| self.path.as_ref().map(PathMode::span))
+            .or_else(|| self.subcommand.as_ref().map(Ident::span))
+    }
+
     pub fn name_prefix(&self) -> String {
-        String::from(if self.short { "-" } else { "--" })
+        String::from(if self.short.is_some() { "-" } else { "--" })
+    }
+
+    /// `::argley::NumberFormat` expression if any of the numeric formatting options are set
+    fn number_format(&self) -> Option<TokenStream> {
+        let mut out = quote! { __argley::NumberFormat::new() };
+        let mut any = false;
+
+        macro_rules! opt {
+            ($($name: ident),+) => {
+                $(
+                    if let Some(ref $name) = self.$name {
+                        out.extend(quote! { .$name(#$name) });
+                        any = true;
+                    }
+                )+
+            };
+        }
+        opt!(precision, radix, prefix, width, pad);
+
+        if any {
+            Some(out)
+        } else {
+            None
+        }
+    }
+
+    /// `::argley::MapFormat` expression if any of the map formatting options are set
+    fn map_format(&self) -> Option<TokenStream> {
+        let mut out = quote! { __argley::MapFormat::new() };
+        let mut any = false;
+
+        macro_rules! opt {
+            ($($name: ident => $method: ident),+) => {
+                $(
+                    if let Some(ref $name) = self.$name {
+                        out.extend(quote! { .$method(#$name) });
+                        any = true;
+                    }
+                )+
+            };
+        }
+        opt!(kv_separator => separator, kv_join => join, kv_repeat_name => repeat_name);
+
+        if any {
+            Some(out)
+        } else {
+            None
+        }
+    }
+
+    /// Format specification to wrap the field in a `::argley::Formatted` with, if any
+    pub fn format_spec(&self) -> Option<TokenStream> {
+        self.number_format()
+            .or_else(|| self.map_format())
+            .or_else(|| self.path.as_ref().map(ToTokens::to_token_stream))
+    }
+
+    /// `FormatArg` or, for fallible specifications, `TryFormatArg` bound the field's type needs for its [format specification](Self::format_spec)
+    pub fn format_spec_bound(&self) -> Option<TokenStream> {
+        if self.number_format_span().is_some() {
+            Some(quote! { __argley::FormatArg<__argley::NumberFormat> })
+        } else if self.map_format_span().is_some() {
+            Some(quote! { __argley::FormatArg<__argley::MapFormat> })
+        } else if self.path.is_some() {
+            Some(
+                quote! { for<'__argley_path> __argley::TryFormatArg<__argley::PathMode<'__argley_path>> },
+            )
+        } else {
+            None
+        }
+    }
+
+    /// Span of the first map formatting option, if any are set
+    fn map_format_span(&self) -> Option<Span> {
+        self.kv_separator
+            .as_ref()
+            .map(LitStr::span)
+            .or_else(|| self.kv_join.as_ref().map(LitStr::span))
+            .or_else(|| self.kv_repeat_name.as_ref().map(LitBool::span))
+    }
+
+    /// Span of the first numeric formatting option, if any are set
+    fn number_format_span(&self) -> Option<Span> {
+        self.precision
+            .as_ref()
+            .map(LitInt::span)
+            .or_else(|| self.radix.as_ref().map(LitInt::span))
+            .or_else(|| self.prefix.as_ref().map(LitStr::span))
+            .or_else(|| self.width.as_ref().map(LitInt::span))
+            .or_else(|| self.pad.as_ref().map(LitChar::span))
+    }
+
+    /// Error out if more than one way of formatting the field is set
+    pub fn check_formatting_conflicts(&self) -> syn::Result<()> {
+        let spans = [
+            self.formatter.as_ref().map(Spanned::span),
+            self.with.as_ref().map(Spanned::span),
+            self.number_format_span(),
+            self.map_format_span(),
+            self.path.as_ref().map(PathMode::span),
+        ];
+
+        match spans.iter().filter_map(Option::as_ref).nth(1) {
+            Some(span) => Err(syn::Error::new(
+                *span,
+                "Only one of `formatter`, `with`, numeric formatting options, map formatting options and `path` may be used on a field",
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Error out on numeric formatting options that don't apply to the field's type, e.g.
+    /// `precision` on an integer. Only checks types that are syntactically primitives, looking
+    /// through single-parameter wrappers such as `Option<T>` & `Vec<T>`.
+    pub fn check_number_format_type(&self, ty: &Type) -> syn::Result<()> {
+        let primitive = match primitive_number(ty) {
+            Some(primitive) => primitive,
+            None => return Ok(()),
+        };
+        let is_float = primitive == "f32" || primitive == "f64";
+
+        if let (Some(precision), false) = (&self.precision, is_float) {
+            return Err(syn::Error::new(
+                precision.span(),
+                format!("`precision` has no effect on `{primitive}`; it only applies to floats"),
+            ));
+        }
+        if let (Some(radix), true) = (&self.radix, is_float) {
+            return Err(syn::Error::new(
+                radix.span(),
+                format!("`radix` has no effect on `{primitive}`; it only applies to integers"),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Globals go before the subcommand, everything else after it
+    fn placement(&self) -> u8 {
+        if self.global.is_some() {
+            0
+        } else if self.subcommand.is_some() {
+            1
+        } else {
+            2
+        }
     }
 
     pub fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.placement().cmp(&other.placement()) {
+            Ordering::Equal => {}
+            ord => return Some(ord),
+        }
+
         Some(match (&self.variadic, &other.variadic) {
             (Some(_), None) => Ordering::Greater,
             (None, Some(_)) => Ordering::Less,
@@ -58,7 +406,7 @@ impl TryFrom<Attribute> for FieldOpts {
                     opts.skip = true;
                 }
                 "short" => {
-                    opts.short = true;
+                    opts.short = Some(ident.clone());
                 }
                 "variadic" => {
                     opts.variadic = Some(ident.clone());
@@ -67,6 +415,7 @@ impl TryFrom<Attribute> for FieldOpts {
                     let literal = parse_eq::<Literal>(meta.input)?;
                     if let Ok(pos) = literal.to_string().parse() {
                         opts.position = Some(pos);
+                        opts.position_span = Some(literal.span());
                     } else {
                         return Err(syn::Error::new(literal.span(), "Position must be a u16"));
                     }
@@ -74,10 +423,107 @@ impl TryFrom<Attribute> for FieldOpts {
                 "formatter" => {
                     opts.formatter = Some(parse_eq(meta.input)?);
                 }
+                "with" => {
+                    opts.with = Some(parse_eq(meta.input)?);
+                }
                 "rename" => {
                     opts.rename = Some(parse_eq(meta.input)?);
                 }
-                _ => return Err(syn::Error::new(ident.span(), "Unknown option")),
+                "precision" => {
+                    opts.precision =
+                        Some(parse_int::<usize>(meta.input, "Precision must be a usize")?);
+                }
+                "radix" => {
+                    let radix = parse_int::<u32>(meta.input, "Radix must be a u32")?;
+                    if !(2..=36).contains(&radix.base10_parse::<u32>()?) {
+                        return Err(syn::Error::new(
+                            radix.span(),
+                            "Radix must be in the range 2..=36",
+                        ));
+                    }
+                    opts.radix = Some(radix);
+                }
+                "prefix" => {
+                    opts.prefix = Some(parse_eq(meta.input)?);
+                }
+                "width" => {
+                    opts.width = Some(parse_int::<usize>(meta.input, "Width must be a usize")?);
+                }
+                "pad" => {
+                    opts.pad = Some(parse_eq(meta.input)?);
+                }
+                "kv_separator" => {
+                    opts.kv_separator = Some(parse_eq(meta.input)?);
+                }
+                "kv_join" => {
+                    opts.kv_join = Some(parse_eq(meta.input)?);
+                }
+                "kv_repeat_name" => {
+                    opts.kv_repeat_name = Some(parse_eq(meta.input)?);
+                }
+                "path" => {
+                    opts.path = Some(parse_eq(meta.input)?);
+                }
+                "range" => {
+                    opts.range = Some(parse_eq(meta.input)?);
+                }
+                "non_empty" => {
+                    opts.non_empty = Some(ident.clone());
+                }
+                "one_of" => {
+                    let one_of = parse_eq::<ExprArray>(meta.input)?;
+                    if one_of.elems.is_empty() {
+                        return Err(syn::Error::new_spanned(
+                            one_of,
+                            "Expected at least one value",
+                        ));
+                    }
+                    opts.one_of = Some(one_of);
+                }
+                "max_len" => {
+                    opts.max_len = Some(parse_int::<usize>(
+                        meta.input,
+                        "Max length must be a usize",
+                    )?);
+                }
+                "exists" => {
+                    opts.exists = Some(ident.clone());
+                }
+                "conflicts_with" => {
+                    opts.conflicts_with.push(parse_eq(meta.input)?);
+                }
+                "requires" => {
+                    opts.requires.push(parse_eq(meta.input)?);
+                }
+                "group" => {
+                    opts.group = Some(parse_eq(meta.input)?);
+                }
+                "required" => {
+                    opts.required = Some(ident.clone());
+                }
+                "global" => {
+                    opts.global = Some(ident.clone());
+                }
+                "subcommand" => {
+                    opts.subcommand = Some(ident.clone());
+                }
+                "value_name" => {
+                    opts.value_name = Some(parse_eq(meta.input)?);
+                }
+                "merge" => {
+                    let mode = parse_eq::<LitStr>(meta.input)?.parse::<Ident>()?;
+                    if !["append", "replace", "deep", "skip"]
+                        .iter()
+                        .any(|m| mode == m)
+                    {
+                        return Err(syn::Error::new(
+                            mode.span(),
+                            "Expected one of `append`, `replace`, `deep`, `skip`",
+                        ));
+                    }
+                    opts.merge = Some(mode);
+                }
+                _ => return Err(unknown_option(ident, &Self::OPTIONS)),
             };
 
             Ok(())
@@ -93,21 +539,134 @@ impl Sum for FieldOpts {
             if opts.skip {
                 acc.skip = true;
             }
-            if opts.short {
-                acc.short = true;
+            if opts.short.is_some() {
+                acc.short = opts.short;
             }
             if opts.variadic.is_some() {
                 acc.variadic = opts.variadic;
             }
             if opts.position.is_some() {
                 acc.position = opts.position;
+                acc.position_span = opts.position_span;
             }
             if opts.rename.is_some() {
                 acc.rename = opts.rename;
             }
+            if opts.formatter.is_some() {
+                acc.formatter = opts.formatter;
+            }
+            if opts.with.is_some() {
+                acc.with = opts.with;
+            }
+            if opts.precision.is_some() {
+                acc.precision = opts.precision;
+            }
+            if opts.radix.is_some() {
+                acc.radix = opts.radix;
+            }
+            if opts.prefix.is_some() {
+                acc.prefix = opts.prefix;
+            }
+            if opts.width.is_some() {
+                acc.width = opts.width;
+            }
+            if opts.pad.is_some() {
+                acc.pad = opts.pad;
+            }
+            if opts.kv_separator.is_some() {
+                acc.kv_separator = opts.kv_separator;
+            }
+            if opts.kv_join.is_some() {
+                acc.kv_join = opts.kv_join;
+            }
+            if opts.kv_repeat_name.is_some() {
+                acc.kv_repeat_name = opts.kv_repeat_name;
+            }
+            if opts.path.is_some() {
+                acc.path = opts.path;
+            }
+            if opts.range.is_some() {
+                acc.range = opts.range;
+            }
+            if opts.non_empty.is_some() {
+                acc.non_empty = opts.non_empty;
+            }
+            if opts.one_of.is_some() {
+                acc.one_of = opts.one_of;
+            }
+            if opts.max_len.is_some() {
+                acc.max_len = opts.max_len;
+            }
+            if opts.exists.is_some() {
+                acc.exists = opts.exists;
+            }
+            acc.conflicts_with.extend(opts.conflicts_with);
+            acc.requires.extend(opts.requires);
+            if opts.group.is_some() {
+                acc.group = opts.group;
+            }
+            if opts.required.is_some() {
+                acc.required = opts.required;
+            }
+            if opts.global.is_some() {
+                acc.global = opts.global;
+            }
+            if opts.subcommand.is_some() {
+                acc.subcommand = opts.subcommand;
+            }
+            if opts.value_name.is_some() {
+                acc.value_name = opts.value_name;
+            }
+            if opts.merge.is_some() {
+                acc.merge = opts.merge;
+            }
 
             acc
         })
         .unwrap_or_default()
     }
 }
+
+/// Name of the primitive number type, if `ty` is one or wraps one in single-parameter types
+fn primitive_number(ty: &Type) -> Option<String> {
+    const NUMBERS: [&str; 14] = [
+        "i8", "u8", "i16", "u16", "i32", "u32", "i64", "u64", "i128", "u128", "isize", "usize",
+        "f32", "f64",
+    ];
+
+    let segment = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+        Type::Reference(reference) => return primitive_number(&reference.elem),
+        Type::Paren(paren) => return primitive_number(&paren.elem),
+        _ => return None,
+    };
+
+    match segment.arguments {
+        PathArguments::None => {
+            let ident = segment.ident.to_string();
+            if NUMBERS.contains(&ident.as_str()) {
+                Some(ident)
+            } else {
+                None
+            }
+        }
+        PathArguments::AngleBracketed(ref args) if args.args.len() == 1 => match args.args[0] {
+            GenericArgument::Type(ref inner) => primitive_number(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn parse_int<T>(input: syn::parse::ParseStream, error_msg: &str) -> syn::Result<LitInt>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    let literal = parse_eq::<LitInt>(input)?;
+    if literal.base10_parse::<T>().is_ok() {
+        Ok(literal)
+    } else {
+        Err(syn::Error::new(literal.span(), error_msg))
+    }
+}
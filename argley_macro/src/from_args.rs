@@ -0,0 +1,339 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::spanned::Spanned;
+use syn::{Data, DataEnum, DeriveInput, Fields, Generics, LitStr};
+
+use crate::bounds;
+use crate::container_opts::ContainerOpts;
+use crate::parsed_variant::ParsedVariant;
+use crate::struct_field::{StructField, TypedFields};
+
+/// Generate the `FromArgs` derive's implementation
+pub fn derive(input: DeriveInput) -> syn::Result<TokenStream> {
+    let DeriveInput {
+        attrs,
+        ident: type_name,
+        generics,
+        data,
+        ..
+    } = input;
+
+    let opts = ContainerOpts::try_from(attrs)?;
+    if let Some(ident) = opts
+        .subcommand
+        .as_ref()
+        .or(opts.flag_enum.as_ref())
+        .or(opts.as_repr.as_ref())
+    {
+        return Err(syn::Error::new(
+            ident.span(),
+            format!("`{ident}` is not supported by `FromArgs`"),
+        ));
+    }
+    if opts.to_string {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`to_string` is not supported by `FromArgs`",
+        ));
+    }
+
+    let impls = match data {
+        Data::Struct(data) => {
+            if let Some(ref ident) = opts.value_enum {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    "`value_enum` is only supported on enums",
+                ));
+            }
+            struct_impls(&opts, &type_name, generics, data.fields)?
+        }
+        Data::Enum(data) => match opts.value_enum {
+            Some(_) => enum_impls(&opts, &type_name, &generics, data)?,
+            None => {
+                return Err(syn::Error::new(
+                    data.enum_token.span(),
+                    "`FromArgs` only supports structs & `value_enum` enums",
+                ));
+            }
+        },
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span(),
+                "`FromArgs` only supports structs & `value_enum` enums",
+            ));
+        }
+    };
+
+    let krate = match opts.krate {
+        Some(ref krate) => krate.to_token_stream(),
+        None => quote! { ::argley },
+    };
+
+    Ok(quote! {
+        const _: () = {
+            use #krate as __argley;
+
+            #impls
+        };
+    })
+}
+
+fn struct_impls(
+    opts: &ContainerOpts,
+    struct_name: &Ident,
+    mut generics: Generics,
+    src_fields: Fields,
+) -> syn::Result<TokenStream> {
+    let fields = Vec::from(StructField::collect_from_fields(src_fields.clone(), true)?);
+
+    for field in &fields {
+        if let Some(span) = field.opts.irreversible_span() {
+            return Err(syn::Error::new(
+                span,
+                "This option is not supported by `FromArgs`",
+            ));
+        }
+    }
+
+    let types = fields.iter().map(move |field| &field.ty);
+    bounds::add_field_bounds(&mut generics, types, &quote! { __argley::FromArgValue });
+
+    let parser = Ident::new("parser", Span::call_site());
+    let binding = move |field: &StructField| format_ident!("__argley_field_{}", field.idx);
+
+    let (mut positional, named): (Vec<_>, Vec<_>) = fields
+        .iter()
+        .partition(move |f| f.opts.position.is_some() || f.opts.variadic.is_some());
+    positional.sort_by(move |a, b| StructField::cmp(a, b));
+
+    // Arguments to leave for the fields output without a name after a field
+    let positional_reserves = positional
+        .iter()
+        .map(move |f| {
+            let ty = &f.ty;
+            quote! { + ::std::matches!(<#ty as __argley::FromArgValue>::ARITY, __argley::Arity::One) as usize }
+        })
+        .collect::<Vec<_>>();
+    let named_reserves = named
+        .iter()
+        .map(move |f| {
+            let ty = &f.ty;
+            quote! {
+                + (<#ty as __argley::FromArgValue>::DROPS_NAME
+                    && ::std::matches!(<#ty as __argley::FromArgValue>::ARITY, __argley::Arity::One)) as usize
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let named_stmts = named.iter().enumerate().map(|(idx, field)| {
+        let ident = binding(field);
+        let ty = &field.ty;
+        let label = &field.label;
+        let name = field.arg_name();
+        let later = named[idx + 1..].iter().filter_map(move |f| f.arg_name());
+        let reserved = named_reserves[idx + 1..].iter().chain(&positional_reserves);
+        quote! {
+            let #ident = #parser.named::<#ty>(#name, #label, &[#(#later),*], 0 #(#reserved)*)?;
+        }
+    });
+
+    let positional_stmts = positional.iter().enumerate().map(|(idx, field)| {
+        let ident = binding(field);
+        let ty = &field.ty;
+        let label = &field.label;
+        let reserved = &positional_reserves[idx + 1..];
+        quote! { let #ident = #parser.positional::<#ty>(#label, 0 #(#reserved)*)?; }
+    });
+
+    let shape_checks = {
+        let named_arities = named.iter().map(move |f| {
+            let ty = &f.ty;
+            quote! { <#ty as __argley::FromArgValue>::ARITY }
+        });
+        let drops_name = named.iter().map(move |f| {
+            let ty = &f.ty;
+            quote! { <#ty as __argley::FromArgValue>::DROPS_NAME }
+        });
+        let positional_arities = positional.iter().map(move |f| {
+            let ty = &f.ty;
+            quote! { <#ty as __argley::FromArgValue>::ARITY }
+        });
+
+        // Spanned on the field so that the error points at it
+        let named_checks = named.iter().enumerate().map(move |(idx, f)| {
+            quote_spanned! { f.ty.span()=>
+                __argley::ArgParser::check_named(named, drops_name, #idx);
+            }
+        });
+        let positional_checks = positional.iter().enumerate().map(move |(idx, f)| {
+            quote_spanned! { f.ty.span()=>
+                __argley::ArgParser::check_positional(named, drops_name, positional, #idx);
+            }
+        });
+
+        quote! {
+            let named: &[__argley::Arity] = &[#(#named_arities),*];
+            let drops_name: &[bool] = &[#(#drops_name),*];
+            let positional: &[__argley::Arity] = &[#(#positional_arities),*];
+            #(#named_checks)*
+            #(#positional_checks)*
+        }
+    };
+
+    let construct = {
+        let values = src_fields.iter().enumerate().map(|(idx, src)| {
+            let value = match fields.iter().find(move |f| f.idx == idx) {
+                Some(field) => binding(field).into_token_stream(),
+                None => quote! { ::std::default::Default::default() },
+            };
+            match src.ident {
+                Some(ref ident) => quote! { #ident: #value },
+                None => value,
+            }
+        });
+
+        match src_fields {
+            Fields::Named(_) => quote! { Self { #(#values),* } },
+            Fields::Unnamed(_) => quote! { Self(#(#values),*) },
+            Fields::Unit => quote! { Self },
+        }
+    };
+
+    let static_args = match opts.static_args {
+        Some(ref args) => args.elems.iter().collect::<Vec<_>>(),
+        None => Vec::new(),
+    };
+    let drop_name = opts.drop_name;
+    let (g1, g2, g3) = generics.split_for_impl();
+
+    // Generic structs only get checked once `from_args` gets instantiated
+    let check_now = if generics.params.is_empty() {
+        Some(quote! { const _: () = <#struct_name>::__ARGLEY_FROM_ARGS_SHAPE; })
+    } else {
+        None
+    };
+
+    Ok(quote! {
+        impl #g1 #struct_name #g2 #g3 {
+            #[doc(hidden)]
+            const __ARGLEY_FROM_ARGS_SHAPE: () = {
+                #shape_checks
+            };
+        }
+
+        #check_now
+
+        #[automatically_derived]
+        impl #g1 __argley::FromArgs for #struct_name #g2 #g3 {
+            fn from_args<S: ::std::convert::AsRef<::std::ffi::OsStr>>(args: &[S]) -> ::std::result::Result<Self, __argley::FromArgsError> {
+                #[allow(clippy::let_unit_value)]
+                let () = Self::__ARGLEY_FROM_ARGS_SHAPE;
+                let mut #parser = __argley::ArgParser::new(args, &[#(#static_args),*])?;
+
+                #(#named_stmts)*
+                #(#positional_stmts)*
+                #parser.finish()?;
+
+                ::std::result::Result::Ok(#construct)
+            }
+        }
+
+        /// Parses the struct from a nested field's values
+        #[automatically_derived]
+        impl #g1 __argley::FromArgValue for #struct_name #g2 #g3 {
+            const ARITY: __argley::Arity = __argley::Arity::Many;
+            const DROPS_NAME: bool = #drop_name;
+
+            #[inline]
+            fn from_values(values: &[&::std::ffi::OsStr]) -> ::std::result::Result<Self, __argley::InvalidValue> {
+                ::std::result::Result::Ok(<Self as __argley::FromArgs>::from_args(values)?)
+            }
+        }
+    })
+}
+
+/// `value_enum`s parse their variants' names
+fn enum_impls(
+    opts: &ContainerOpts,
+    enum_name: &Ident,
+    generics: &Generics,
+    data: DataEnum,
+) -> syn::Result<TokenStream> {
+    if let Some(ref args) = opts.static_args {
+        return Err(syn::Error::new(
+            args.span(),
+            "`static_args` is not supported by `FromArgs` on enums",
+        ));
+    }
+
+    let rule = opts.rename_all.unwrap_or_default();
+    let mut arms = Vec::with_capacity(data.variants.len());
+    let mut names = Vec::with_capacity(data.variants.len());
+
+    for variant in data.variants {
+        let variant = ParsedVariant::try_from(variant)?;
+        if variant.opts.skip {
+            continue;
+        }
+        if !matches!(variant.fields, TypedFields::Unit) {
+            return Err(syn::Error::new(
+                variant.ident.span(),
+                "`FromArgs` only supports unit variants",
+            ));
+        }
+        if let Some(span) = variant
+            .opts
+            .value
+            .as_ref()
+            .map(Spanned::span)
+            .or_else(|| variant.opts.static_args.as_ref().map(Spanned::span))
+        {
+            return Err(syn::Error::new(
+                span,
+                "This option is not supported by `FromArgs`",
+            ));
+        }
+
+        let name = variant.renamed(rule);
+        let ident = &variant.ident;
+        arms.push(quote! { #name => ::std::result::Result::Ok(Self::#ident), });
+        names.push(format!("`{}`", name.value()));
+    }
+
+    let expected = LitStr::new(
+        &format!("expected one of {}", names.join(", ")),
+        Span::call_site(),
+    );
+    let label = LitStr::new(&enum_name.to_string(), enum_name.span());
+    let drop_name = opts.drop_name;
+    let (g1, g2, g3) = generics.split_for_impl();
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #g1 __argley::FromArgValue for #enum_name #g2 #g3 {
+            const DROPS_NAME: bool = #drop_name;
+
+            fn from_values(values: &[&::std::ffi::OsStr]) -> ::std::result::Result<Self, __argley::InvalidValue> {
+                let value = <::std::string::String as __argley::FromArgValue>::from_values(values)?;
+                match value.as_str() {
+                    #(#arms)*
+                    _ => ::std::result::Result::Err(__argley::InvalidValue {
+                        value: ::std::convert::From::from(value),
+                        reason: ::std::convert::From::from(#expected),
+                    }),
+                }
+            }
+        }
+
+        #[automatically_derived]
+        impl #g1 __argley::FromArgs for #enum_name #g2 #g3 {
+            fn from_args<S: ::std::convert::AsRef<::std::ffi::OsStr>>(args: &[S]) -> ::std::result::Result<Self, __argley::FromArgsError> {
+                let mut parser = __argley::ArgParser::new(args, &[])?;
+                let value = parser.positional::<Self>(#label, 0)?;
+                parser.finish()?;
+
+                ::std::result::Result::Ok(value)
+            }
+        }
+    })
+}
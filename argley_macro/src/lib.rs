@@ -3,47 +3,103 @@
 #![warn(missing_docs)]
 #![allow(clippy::manual_let_else)]
 
-use proc_macro2::{Ident, Span};
+use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote, ToTokens};
 use std::rc::Rc;
 use syn::parse::{Parse, ParseStream};
-use syn::{parse_macro_input, parse_quote, DeriveInput, Generics, Token};
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Expr, ExprLit, Generics, Lit,
+    Meta, Token,
+};
 
 use crate::container_opts::ContainerOpts;
 use crate::parsed_fields::{FunctionSignature, ParsedFields};
 
 mod any_added_wrap;
+mod bounds;
+mod builder;
+mod constraints;
 mod container_opts;
 mod field_ident;
 mod field_opts;
+mod from_args;
+mod merge;
 mod parsed_fields;
 mod parsed_variant;
+mod path_mode;
+mod rename_rule;
+mod schema;
 mod struct_field;
+mod variant_opts;
 
 const ATTR: &str = "arg";
 const OPT_SKIP: &str = "skip";
 const ARG_CONSUMER: &str = "consumer";
 const PROP_ANY_ADDED: &str = "__argley_has_added_value_to_consumer";
+const FORMATTED_VALUE: &str = "__argley_formatted_value";
 
 struct Runtime {
     struct_name: Ident,
     generics: Generics,
     fields: ParsedFields,
+
+    /// The container's doc comment
+    doc: Option<String>,
+
+    /// Variant names of enums; used by `as_repr`
+    variant_idents: Vec<Ident>,
     opts: Rc<ContainerOpts>,
 }
 
-/// Derive the `Arg` trait.
+/// Derive the `Arg` & `TryArg` traits and, with the `schema` container attribute, `ArgSchema`.
+///
+/// Types that can fail to produce their arguments only get `TryArg`; see the `fallible`
+/// container attribute. In that mode formatters may return either `impl TryArg` or
+/// `Result<impl TryArg, impl Into<ArgError>>` and every field must implement `TryArg` or `Arg`.
+///
+/// Containers marked with `#[arg(schema)]` also implement `ArgSchema`, describing the fields'
+/// names, kinds, types & doc comments at runtime & mapping each field that gets output with a
+/// name to that name, e.g. `("output_dir", "--output_dir")`, via `ArgSchema::names`.
+///
+/// Each field's arguments are preceded by a call to `ArgConsumer::enter_field` & followed by one
+/// to `ArgConsumer::exit_field`, & each enum variant's by `enter_variant` & `exit_variant`.
+/// `static_args` get added via `ArgConsumer::add_static_args` & field, subcommand & flag names
+/// via `ArgConsumer::add_name`. This lets consumers such as
+/// `ArgDiff` & `ArgTracer` tell which field produced which arguments.
 ///
 /// # Field attributes
 ///
 /// | Attribute | Description |
 /// |---|---|
-/// | `arg(skip)` | Exclude this property. Unavailable for fields in tuple enum variants. |
-/// | `arg(short)` | Prefix with `-` instead of `--`. Ignored on variadic/positional arguments |
+/// | `arg(skip)` | Exclude this property |
+/// | `arg(short)` | Prefix with `-` instead of `--`. Not allowed on variadic, positional & subcommand fields, nor is `rename` |
 /// | `arg(position = INTEGER)` | Positional argument. |
 /// | `arg(variadic)` | Shorthand for putting an argument in the final position |
 /// | `arg(rename = "new_name")` | Rename the argument |
-/// | `arg(formatter = path::to::formatter)` | Format the field with the given function. Has a signature of `fn(&T) -> impl Arg` |
+/// | `arg(formatter = path::to::formatter)` | Format the field with the given function or closure, e.g. `formatter = \|v: &u8\| v * 2`. Has a signature of `fn(&T) -> impl Arg` |
+/// | `arg(with = path::to::module)` | Add the field via the module's `fn add_to(&T, &str, &mut impl ArgConsumer) -> bool` & `fn add_unnamed_to(&T, &mut impl ArgConsumer) -> bool`, which receive the name & consumer directly. In `fallible` containers they may return `Result<bool, impl Into<ArgError>>`. |
+/// | `arg(subcommand)` | Mark the field holding a `subcommand` enum. It gets output without a name, after the `global` fields & before all the others. |
+/// | `arg(global)` | Output the field before the `subcommand` field regardless of the declaration order, e.g. for `git -C dir commit`. Requires a `subcommand` field alongside it. |
+/// | `arg(precision = INTEGER)` | Number of decimal places to format floats with. Applies to numbers inside `Option`s & collections too. A compile error on integer fields. |
+/// | `arg(radix = INTEGER)` | Radix to format integers in, e.g. `16` for hex. A compile error on float fields. |
+/// | `arg(prefix = "0x")` | Output this string between the sign and the digits of a number |
+/// | `arg(width = INTEGER)` | Minimum width of a formatted number |
+/// | `arg(pad = '0')` | Character to pad a number with when it's shorter than its `width`. `'0'` pads after the prefix, anything else in front of it. Defaults to a space |
+/// | `arg(kv_separator = ":")` | String to separate the keys & values of a map with. Defaults to `=`. Applies to maps inside `Option`s too. |
+/// | `arg(kv_join = ",")` | Join all the entries of a map into a single argument with this string |
+/// | `arg(kv_repeat_name = false)` | Output the name of a map once instead of before every entry |
+/// | `arg(path = "MODE")` | Normalise a `Path`/`PathBuf` field, including ones inside `Option`s & collections. `MODE` is one of `expand_home`, `absolute`, `canonical` or `relative_to(EXPRESSION)`. A leading `~` is always expanded to the home directory. Requires the container to be `fallible`; paths that fail to resolve produce an error. |
+/// | `arg(range = 1..=65535)` | Error if a value is outside the given range. Checks each value inside `Option`s & collections. Requires the container to be `fallible`, as do all the validators below. |
+/// | `arg(non_empty)` | Error if the field is an empty string, path or collection. `None` passes. |
+/// | `arg(one_of = ["fast", "slow"])` | Error if a value isn't in the list. Checks each value inside `Option`s & collections. |
+/// | `arg(max_len = INTEGER)` | Error if the field's string length (in `char`s) or number of elements exceeds the limit. `None` passes. |
+/// | `arg(exists)` | Error if a path doesn't exist. Checks each value inside `Option`s & collections. |
+/// | `arg(conflicts_with = "field")` | Error if both this field & the named one produce arguments. Repeatable. Structs only; requires the container to be `fallible`, as do the constraints below. |
+/// | `arg(requires = "field")` | Error if this field produces arguments but the named one doesn't. Repeatable. |
+/// | `arg(group = "name")` | Add the field to a named group for use with `required` |
+/// | `arg(required)` | Error if the field doesn't produce any arguments. On a grouped field, error if none of the group's fields do. |
+/// | `arg(value_name = "DIR")` | Placeholder for the field's value in `Schema::usage` & `Schema::markdown`. Defaults to the field's name in `SCREAMING_SNAKE_CASE`. |
+/// | `arg(merge = "replace")` | How the `Merge` derive combines the field: `append` (the default) appends collections, `replace` keeps the later collection if it's not empty, `deep` merges the contents of two `Some`s via `MergeDeep` & `skip` keeps the original value |
 ///
 /// # Container attributes
 ///
@@ -51,34 +107,90 @@ struct Runtime {
 /// |---|---|
 /// | `arg(drop_name)` | Derive an `Arg::add_to` that ignores its `name` parameter |
 /// | `arg(to_string)` | Derive an `Arg::add_unnamed_to` that uses `self.to_string()` as the argument |
-/// | `arg(as_repr)` | For use on enums - use `(*self as REPR)` as the argument on enums with `#[repr(INT)]` |
+/// | `arg(as_repr)` | For use on enums - use `(Self::Variant as REPR)` as the argument on enums with `#[repr(INT)]`; doesn't require `Copy` |
 /// | `arg(static_args = ["--arg1", "value1", "--foobar"])` | Always output this set of args regardless of any struct properties |
+/// | `arg(value_enum)` | For use on enums - make unit variants without a `value` output their name, e.g. `Format::Json` outputs `json` & a `format: Format` field outputs `--format json` |
+/// | `arg(subcommand)` | For use on enums - make each variant output its name followed by its fields, e.g. `Cmd::Build { release: true }` outputs `build --release`. Implies `drop_name`, so a `cmd: Cmd` field outputs `build --release` too. Variants can hold nested subcommand enums, e.g. `Compose(ComposeCmd)` for `docker compose up`. |
+/// | `arg(flag_enum)` | For use on enums - make each variant output its name as a flag followed by its fields, e.g. `Compression::Gzip` outputs `--gzip`. Implies `drop_name`, so a `compression: Compression` field outputs exactly one flag. |
+/// | `arg(short)` | For use with `flag_enum` - prefix the flags with `-` instead of `--` |
+/// | `arg(rename_all = "kebab-case")` | How `value_enum`, `subcommand` & `flag_enum` convert variant names. One of `lowercase`, `UPPERCASE`, `PascalCase`, `camelCase`, `snake_case`, `SCREAMING_SNAKE_CASE`, `kebab-case` (the default) or `SCREAMING-KEBAB-CASE` |
+/// | `arg(fallible)` | Only derive `TryArg`, propagating errors from fields & formatters. Required by options that can fail, e.g. `path`, validators & constraints. Fields whose type only implements `Arg` get added through it. |
+/// | `arg(bound = "T: Arg")` | `where` predicates to use instead of the inferred `FieldType: Arg` ones; `""` adds none. By default every field whose type uses a type parameter gets bound by `Arg`, or `TryArg` if the container is `fallible`, except for `PhantomData` fields & ones using a `formatter` or `with` module. Fields using a format specification get bound by `FormatArg` of it instead, e.g. `T: FormatArg<NumberFormat>` for `precision` or `T: for<'a> TryFormatArg<PathMode<'a>>` for `path`. Can't be combined with `to_string` or `as_repr`. |
+/// | `arg(crate = "path::to::argley")` | Path to the `argley` crate to use in the generated code, for when it is renamed or reached through a re-export. Defaults to `::argley`. |
+/// | `arg(required_one_of = ["field1", "field2"])` | Error if none of the given fields produce arguments. Repeatable. Structs only; requires the container to be `fallible`. |
+/// | `arg(schema)` | Also implement `ArgSchema`, e.g. for `Schema::usage` or `ArgTracer::of` |
 ///
 /// # Variant attributes
 ///
 /// | Attribute | Description |
 /// |---|---|
 /// | `arg(value = EXPRESSION)` | Make the given variant push the given expression as its arguments (e.g. `&[&str]` or `PathBuf`) |
+/// | `arg(rename = "name")` | Name to output for the variant instead of the `rename_all`-converted one. Requires `value_enum`, `subcommand` or `flag_enum` on the enum. |
+/// | `arg(static_args = ["--arg1", "value1"])` | Output this set of args before the variant's fields, after the container's `static_args` & the subcommand name |
+/// | `arg(short)` | Prefix all of the variant's fields, as well as its `flag_enum` flag, with `-` instead of `--` |
+/// | `arg(drop_name)` | Make `Arg::add_to` ignore its `name` parameter when called on this variant |
+/// | `arg(skip)` | Make the variant output nothing, including its name in `Arg::add_to` |
 #[proc_macro_derive(Arg, attributes(arg))]
 pub fn derive_args(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let Runtime {
         struct_name,
         mut generics,
         fields,
+        doc,
+        variant_idents,
         opts,
     } = parse_macro_input!(input as Runtime);
 
     let consumer = new_ident(ARG_CONSUMER);
 
-    let named_impl = if opts.drop_name {
-        Some(quote! {
-            #[inline]
-            fn add_to(&self, _: &str, #consumer: &mut impl ::argley::ArgConsumer) -> bool {
-                ::argley::Arg::add_unnamed_to(self, #consumer)
+    let named_impl = fields.named_impl();
+    let drops_name = if opts.drop_name {
+        quote! { const DROPS_NAME: bool = true; }
+    } else {
+        TokenStream::new()
+    };
+    let schema_impl = if opts.schema {
+        let (g1, g2, g3) = generics.split_for_impl();
+        let name = struct_name.to_string();
+        let doc = match doc {
+            Some(ref doc) => quote! { ::std::option::Option::Some(#doc) },
+            None => quote! { ::std::option::Option::None },
+        };
+        let kind = fields.schema_kind();
+        let names = fields.arg_names();
+        let names_len = names.len();
+        let names = names
+            .into_iter()
+            .map(move |(label, name, drops_name)| quote! { (#label, #name, #drops_name) });
+
+        quote! {
+            impl #g1 #struct_name #g2 #g3 {
+                #[doc(hidden)]
+                const __ARGLEY_SCHEMA: __argley::Schema = {
+                    use __argley::{NotArg as _, NotTryArg as _};
+                    __argley::Schema::new(#name, #doc, #kind)
+                };
             }
-        })
+
+            #[automatically_derived]
+            impl #g1 __argley::ArgSchema for #struct_name #g2 #g3 {
+                fn schema() -> &'static __argley::Schema {
+                    &Self::__ARGLEY_SCHEMA
+                }
+
+                fn names() -> ::std::vec::Vec<(&'static str, &'static str)> {
+                    use __argley::{NotArg as _, NotTryArg as _};
+                    let names: [(&'static str, &'static str, bool); #names_len] = [#(#names),*];
+                    names
+                        .into_iter()
+                        .filter(move |entry| !entry.2)
+                        .map(move |entry| (entry.0, entry.1))
+                        .collect()
+                }
+            }
+        }
     } else {
-        None
+        TokenStream::new()
     };
 
     let fields = if opts.to_string {
@@ -93,42 +205,192 @@ pub fn derive_args(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 
         quote! {
             #sig {
-                ::argley::ArgConsumer::add_arg(#consumer, ::std::string::ToString::to_string(self));
+                __argley::ArgConsumer::add_arg(#consumer, ::std::string::ToString::to_string(self));
                 true
             }
         }
     } else if let Some(as_repr) = &opts.as_repr {
-        if has_generics(&generics) {
+        let sig = FunctionSignature::with_consumer(true);
+
+        // Casting the variant paths rather than `self` avoids requiring `Copy`
+        let arms = variant_idents.iter().map(move |ident| {
+            quote! { Self::#ident => Self::#ident as #as_repr, }
+        });
+
+        quote! {
+            #sig {
+                let value = match *self {
+                    #(#arms)*
+                };
+                __argley::Arg::add_unnamed_to(&value, #consumer)
+            }
+        }
+    } else {
+        if let Some(ref bound) = opts.bound {
             generics
                 .make_where_clause()
                 .predicates
-                .push(parse_quote! { Self: ::std::borrow::ToOwned });
+                .extend(bound.iter().cloned());
+        } else {
+            let bound = if opts.fallible {
+                quote! { __argley::TryArg }
+            } else {
+                quote! { __argley::Arg }
+            };
+            // Fields rendered through a `formatter`, `with` module or format specification don't
+            // need to implement the trait themselves
+            let types = fields.all_fields().into_iter().filter_map(move |field| {
+                let opts = &field.opts;
+                if opts.formatter.is_none() && opts.with.is_none() && opts.format_spec().is_none() {
+                    Some(&field.ty)
+                } else {
+                    None
+                }
+            });
+            bounds::add_field_bounds(&mut generics, types, &bound);
+
+            // Fields with a format specification need to support it instead
+            for field in fields.all_fields() {
+                if let Some(bound) = field.opts.format_spec_bound() {
+                    bounds::add_field_bounds(&mut generics, [&field.ty], &bound);
+                }
+            }
         }
 
-        let sig = FunctionSignature::with_consumer(true);
+        fields.into_token_stream()
+    };
+
+    let (g1, g2, g3) = generics.split_for_impl();
 
+    let impls = if opts.fallible {
         quote! {
-            #sig {
-                ::argley::Arg::add_unnamed_to(&(::std::borrow::ToOwned::to_owned(self) as #as_repr), #consumer)
+            #[automatically_derived]
+            impl #g1 __argley::TryArg for #struct_name #g2 #g3 {
+                #drops_name
+
+                #named_impl
+
+                #fields
             }
         }
     } else {
-        fields.into_token_stream()
+        let name = new_ident("name");
+
+        quote! {
+            #[automatically_derived]
+            impl #g1 __argley::Arg for #struct_name #g2 #g3 {
+                #drops_name
+
+                #named_impl
+
+                #fields
+            }
+
+            #[automatically_derived]
+            impl #g1 __argley::TryArg for #struct_name #g2 #g3 {
+                #drops_name
+
+                #[inline]
+                fn try_add_to(&self, #name: &str, #consumer: &mut impl __argley::ArgConsumer) -> ::std::result::Result<bool, __argley::ArgError> {
+                    ::std::result::Result::Ok(__argley::Arg::add_to(self, #name, #consumer))
+                }
+
+                #[inline]
+                fn try_add_unnamed_to(&self, #consumer: &mut impl __argley::ArgConsumer) -> ::std::result::Result<bool, __argley::ArgError> {
+                    ::std::result::Result::Ok(__argley::Arg::add_unnamed_to(self, #consumer))
+                }
+            }
+        }
     };
 
-    let (g1, g2, g3) = generics.split_for_impl();
+    // Generated code refers to the crate via this alias so that `crate = "..."` only needs to
+    // be applied in one place
+    let krate = match opts.krate {
+        Some(ref krate) => krate.to_token_stream(),
+        None => quote! { ::argley },
+    };
 
     (quote! {
-        #[automatically_derived]
-        impl #g1 ::argley::Arg for #struct_name #g2 #g3 {
-            #named_impl
+        const _: () = {
+            use #krate as __argley;
 
-            #fields
-        }
+            #schema_impl
+
+            #impls
+        };
     })
     .into()
 }
 
+/// Derive a fluent builder for a struct with named fields, e.g. `FooBuilder` for `Foo`.
+///
+/// `Foo::builder()` starts from `Foo::default()`, so the struct must implement `Default`. The
+/// builder has one setter per field, named after the field & sharing its visibility & doc
+/// comment. Setters take `impl Into<T>`; those of `Option<T>` fields take `impl Into<T>` & wrap
+/// it in `Some`, with a `clear_<field>` method resetting the field to `None`. Fields named after
+/// one of the builder's methods, e.g. `build`, are compile errors. Finish with `build()`, `Foo::from(builder)` or, for types deriving `Arg`, go
+/// straight to a consumer with `add_arg_set(&mut consumer)` (`try_add_arg_set` for `fallible`
+/// types) from the `ArgSetBuilder` trait the builder implements.
+#[proc_macro_derive(ArgBuilder, attributes(arg))]
+pub fn derive_arg_builder(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    builder::derive(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive `FromArgs`, parsing the arguments produced by the `Arg` derive back into the struct or
+/// `value_enum`.
+///
+/// Uses the same attributes as `Arg`: `rename`, `short`, `position`, `variadic`, `static_args`,
+/// `drop_name`, `rename_all` & `crate` are honoured & `skip`ped fields get their `Default` value.
+/// Every other field must implement `FromArgValue`, which is implemented for the standard
+/// numbers, strings, paths, `char`s, `bool`s, `Option`s, collections & maps in their default
+/// format, as well as for the types deriving `FromArgs`, so derived structs can be nested. Options
+/// whose output can't be parsed back, e.g. `formatter`, `with`, `precision`, `radix`, `path`,
+/// the map options or `subcommand`, are compile errors, as are `subcommand`, `flag_enum`,
+/// `as_repr` & `to_string` containers & `value_enum` variants with fields or a `value`.
+///
+/// The arguments are read in the order `Arg` outputs them: `static_args`, named fields in
+/// declaration order, then positional fields by position. Each field consumes as many values as
+/// its type's `FromArgValue::ARITY` says, so a value equal to another field's name is read as a
+/// value. Fields taking several values, e.g. `Vec`s, stop at the name of a later field or when
+/// only enough arguments remain for the required fields output without a name.
+///
+/// Shapes whose output is ambiguous fail to compile, including under `cargo check` for
+/// non-generic structs: positional `bool`s, more than one optional or multi-value field output
+/// without a name, e.g. positional or `drop_name` ones, or one alongside a multi-value named
+/// field before it. What remains ambiguous is data-dependent: the values of a multi-value field
+/// that equal a later field's name end it early, as do map keys containing a `=`.
+#[proc_macro_derive(FromArgs, attributes(arg))]
+pub fn derive_from_args(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    from_args::derive(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive `Merge`, combining layered instances of a type, e.g. defaults, a config file &
+/// overrides, into one.
+///
+/// Each field gets merged via its own `Merge` impl: `Option`s get overridden when the later
+/// value is `Some`, `bool`s get OR-ed together, collections get appended to (or replaced when
+/// non-empty if the field has `merge = "replace"`) & other values get overridden. Fields whose
+/// types derive `Merge` themselves get merged recursively. Enum values of the same variant merge
+/// field by field; a different variant replaces the value.
+///
+/// The `merge` field attribute changes how a field gets merged: `merge = "deep"` merges the
+/// contents of an `Option` whose type implements `Merge` when both values are `Some`, &
+/// `merge = "skip"` keeps the original value. Fields `skip`ped by the `Arg` derive still get
+/// merged.
+#[proc_macro_derive(Merge, attributes(arg))]
+pub fn derive_merge(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    merge::derive(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
 impl Parse for Runtime {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let DeriveInput {
@@ -139,15 +401,24 @@ impl Parse for Runtime {
             ..
         } = input.parse::<DeriveInput>()?;
 
-        let opts = Rc::new(ContainerOpts::try_from(attrs)?);
+        let doc = doc_comment(&attrs);
+        let opts = ContainerOpts::try_from(attrs)?;
+        let variant_idents = match data {
+            Data::Enum(ref data) => data.variants.iter().map(move |v| v.ident.clone()).collect(),
+            _ => Vec::new(),
+        };
+
+        let fields = if opts.should_collect_enum_fields() {
+            ParsedFields::from_data(opts, data)
+        } else {
+            ParsedFields::new_empty(opts, &data)
+        }?;
 
         Ok(Self {
-            fields: if opts.should_collect_enum_fields() {
-                ParsedFields::from_data(opts.clone(), data)
-            } else {
-                ParsedFields::new_empty(opts.clone(), &data)
-            }?,
-            opts,
+            opts: fields.container_opts().clone(),
+            fields,
+            doc,
+            variant_idents,
             struct_name,
             generics,
         })
@@ -186,6 +457,51 @@ fn parse_eq<T: Parse>(stream: ParseStream) -> syn::Result<T> {
     stream.parse()
 }
 
+/// Error for an option name not in `expected`, listing the valid ones
+fn unknown_option(ident: &Ident, expected: &[&str]) -> syn::Error {
+    let expected = expected
+        .iter()
+        .map(move |opt| format!("`{opt}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    syn::Error::new(
+        ident.span(),
+        format!("Unknown option `{ident}`. Expected one of {expected}"),
+    )
+}
+
+/// Contents of the `#[doc = "..."]` attributes, i.e. doc comments, with the leading space of each
+/// line removed
+fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines = attrs
+        .iter()
+        .filter_map(move |attr| match attr.meta {
+            Meta::NameValue(ref nv) if nv.path.is_ident("doc") => match nv.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(ref lit),
+                    ..
+                }) => Some(lit.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let doc = lines
+        .iter()
+        .map(move |line| line.strip_prefix(' ').unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let doc = doc.trim();
+
+    if doc.is_empty() {
+        None
+    } else {
+        Some(doc.to_owned())
+    }
+}
+
 fn has_generics(generics: &Generics) -> bool {
     !(generics.params.is_empty() && generics.where_clause.is_none())
 }
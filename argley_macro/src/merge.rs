@@ -0,0 +1,204 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Fields, Index, Member, Type};
+
+use crate::container_opts::ContainerOpts;
+use crate::field_opts::FieldOpts;
+use crate::{bounds, ATTR};
+
+/// Generate the `Merge` derive's implementation
+pub fn derive(input: DeriveInput) -> syn::Result<TokenStream> {
+    let DeriveInput {
+        attrs,
+        ident: type_name,
+        mut generics,
+        data,
+        ..
+    } = input;
+
+    let opts = ContainerOpts::try_from(attrs)?;
+    let other = Ident::new("other", type_name.span());
+
+    let (body, fields) = match data {
+        Data::Struct(data) => {
+            let fields = MergeField::collect(data.fields)?;
+            let stmts = fields.iter().filter_map(|field| {
+                let member = &field.member;
+                field.merge_call(quote! { &mut self.#member }, quote! { #other.#member })
+            });
+            let body = quote! { #(#stmts;)* };
+
+            (body, fields)
+        }
+        Data::Enum(data) => {
+            let mut all_fields = Vec::new();
+            let mut arms = Vec::with_capacity(data.variants.len());
+
+            for variant in data.variants {
+                let ident = variant.ident;
+                let named = matches!(variant.fields, Fields::Named(_));
+                let fields = MergeField::collect(variant.fields)?;
+                let this_binding = move |f: &MergeField| format_ident!("__argley_self_{}", f.idx);
+                let other_binding = move |f: &MergeField| format_ident!("__argley_other_{}", f.idx);
+
+                let pat = |binding: &dyn Fn(&MergeField) -> Ident| {
+                    if fields.is_empty() {
+                        return quote! { Self::#ident { .. } };
+                    }
+
+                    let bindings = fields.iter().map(move |f| {
+                        let binding = if f.is_skipped() {
+                            quote! { _ }
+                        } else {
+                            binding(f).into_token_stream()
+                        };
+                        if named {
+                            let member = &f.member;
+                            quote! { #member: #binding }
+                        } else {
+                            binding
+                        }
+                    });
+                    if named {
+                        quote! { Self::#ident { #(#bindings),* } }
+                    } else {
+                        quote! { Self::#ident(#(#bindings),*) }
+                    }
+                };
+                let (this_pat, other_pat) = (pat(&this_binding), pat(&other_binding));
+
+                let stmts = fields.iter().filter_map(|field| {
+                    let this = this_binding(field);
+                    let other = other_binding(field);
+                    field.merge_call(this.into_token_stream(), other.into_token_stream())
+                });
+                arms.push(quote! {
+                    (#this_pat, #other_pat) => { #(#stmts;)* }
+                });
+
+                all_fields.extend(fields);
+            }
+
+            let body = quote! {
+                match (self, #other) {
+                    #(#arms)*
+                    #[allow(unreachable_patterns)]
+                    (this, #other) => {
+                        *this = #other;
+                    }
+                }
+            };
+
+            (body, all_fields)
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span(),
+                "`Merge` only supports structs & enums",
+            ));
+        }
+    };
+
+    let (deep, merged): (Vec<_>, Vec<_>) = fields
+        .iter()
+        .filter(move |f| !f.is_skipped())
+        .partition(move |f| f.is_mode("deep"));
+    bounds::add_field_bounds(
+        &mut generics,
+        merged.into_iter().map(move |f| &f.ty),
+        &quote! { __argley::Merge },
+    );
+    bounds::add_field_bounds(
+        &mut generics,
+        deep.into_iter().map(move |f| &f.ty),
+        &quote! { __argley::MergeDeep },
+    );
+
+    let krate = match opts.krate {
+        Some(ref krate) => krate.to_token_stream(),
+        None => quote! { ::argley },
+    };
+    let (g1, g2, g3) = generics.split_for_impl();
+
+    Ok(quote! {
+        const _: () = {
+            use #krate as __argley;
+
+            #[automatically_derived]
+            impl #g1 __argley::Merge for #type_name #g2 #g3 {
+                #[allow(unused_variables)]
+                fn merge(&mut self, #other: Self) {
+                    #body
+                }
+            }
+        };
+    })
+}
+
+/// A field of the struct or variant being merged. Unlike the `Arg` derive's fields, `skip`ped
+/// ones get merged too; `merge = "skip"` keeps the original value instead.
+struct MergeField {
+    idx: usize,
+    member: Member,
+    ty: Type,
+    mode: Option<Ident>,
+}
+
+impl MergeField {
+    fn collect(fields: Fields) -> syn::Result<Vec<Self>> {
+        fields
+            .into_iter()
+            .enumerate()
+            .map(move |(idx, field)| {
+                let opts = field
+                    .attrs
+                    .into_iter()
+                    .filter(move |attr| attr.path().is_ident(ATTR))
+                    .map(FieldOpts::try_from)
+                    .collect::<syn::Result<Vec<_>>>()?
+                    .into_iter()
+                    .sum::<FieldOpts>();
+
+                let member = match field.ident {
+                    Some(ident) => Member::Named(ident),
+                    None => Member::Unnamed(Index {
+                        index: idx.try_into().unwrap_or(u32::MAX),
+                        span: field.ty.span(),
+                    }),
+                };
+
+                Ok(Self {
+                    idx,
+                    member,
+                    ty: field.ty,
+                    mode: opts.merge,
+                })
+            })
+            .collect()
+    }
+
+    fn is_mode(&self, mode: &str) -> bool {
+        matches!(self.mode, Some(ref m) if m == mode)
+    }
+
+    fn is_skipped(&self) -> bool {
+        self.is_mode("skip")
+    }
+
+    /// `Merge::merge`, `Merge::merge_replace` or `MergeDeep::merge_deep` call depending on the
+    /// field's `merge` mode; `None` for `merge = "skip"`
+    fn merge_call(&self, this: TokenStream, other: TokenStream) -> Option<TokenStream> {
+        let span = self.ty.span();
+        Some(match self.mode {
+            Some(ref mode) if mode == "skip" => return None,
+            Some(ref mode) if mode == "replace" => {
+                quote_spanned! { span=> __argley::Merge::merge_replace(#this, #other) }
+            }
+            Some(ref mode) if mode == "deep" => {
+                quote_spanned! { span=> __argley::MergeDeep::merge_deep(#this, #other) }
+            }
+            _ => quote_spanned! { span=> __argley::Merge::merge(#this, #other) },
+        })
+    }
+}
@@ -1,18 +1,21 @@
 use std::rc::Rc;
 
-use proc_macro2::{Delimiter, Group, Ident, Punct, Spacing, TokenStream};
+use proc_macro2::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream};
 use quote::{quote, ToTokens, TokenStreamExt};
 use syn::spanned::Spanned;
-use syn::Data;
+use syn::{Data, ExprArray, LitStr};
 
 use crate::any_added_wrap::AnyAddedWrapper;
+use crate::constraints::Constraints;
 use crate::container_opts::ContainerOpts;
 use crate::parsed_variant::ParsedVariant;
-use crate::struct_field::{StructField, TypedFields};
+use crate::schema;
+use crate::struct_field::StructField;
 use crate::{new_ident, TryCollectStable, ARG_CONSUMER, PROP_ANY_ADDED};
 
 pub struct ParsedFields {
     inner: Inner,
+    constraints: Constraints,
     container_opts: Rc<ContainerOpts>,
 }
 
@@ -25,22 +28,251 @@ impl ParsedFields {
             }
     }
 
-    pub fn new_empty(opts: Rc<ContainerOpts>, data: &Data) -> syn::Result<Self> {
-        Ok(Self {
-            inner: Inner::new_empty(data)?,
-            container_opts: opts,
+    /// All the non-skipped fields of the struct or of every variant
+    pub fn all_fields(&self) -> Vec<&StructField> {
+        match self.inner {
+            Inner::Struct(ref fields) => fields.iter().collect(),
+            Inner::Enum(ref variants) => {
+                variants.iter().flat_map(move |v| v.fields.iter()).collect()
+            }
+        }
+    }
+
+    /// `(field, emitted name, whether the name gets dropped)` entries in declaration order. Enum
+    /// variant names are keyed by the variant & their fields by `Variant.field`. The last
+    /// element is a `bool` expression as only the field's type knows whether it drops the name.
+    pub fn arg_names(&self) -> Vec<(String, Literal, TokenStream)> {
+        fn field_names<'a>(
+            fields: impl IntoIterator<Item = &'a StructField>,
+            prefix: Option<&Ident>,
+            out: &mut Vec<(String, Literal, TokenStream)>,
+        ) {
+            let mut fields = fields.into_iter().collect::<Vec<_>>();
+            fields.sort_by_key(move |f| f.idx);
+
+            for field in fields {
+                if let Some(name) = field.arg_name() {
+                    let label = match prefix {
+                        Some(prefix) => format!("{prefix}.{}", field.label),
+                        None => field.label.clone(),
+                    };
+                    out.push((label, name, schema::drops_name(field)));
+                }
+            }
+        }
+
+        let mut out = Vec::new();
+        match self.inner {
+            Inner::Struct(ref fields) => field_names(fields, None, &mut out),
+            Inner::Enum(ref variants) => {
+                for variant in variants.iter().filter(move |v| !v.opts.skip) {
+                    if let Some(ref name) = variant.name {
+                        out.push((variant.ident.to_string(), name.token(), quote! { false }));
+                    }
+                    field_names(variant.fields.iter(), Some(&variant.ident), &mut out);
+                }
+            }
+        }
+
+        out
+    }
+
+    /// `SchemaKind` expression describing the fields or variants
+    pub fn schema_kind(&self) -> TokenStream {
+        if !self.container_opts.should_collect_enum_fields() {
+            return quote! { __argley::SchemaKind::Value };
+        }
+
+        match self.inner {
+            Inner::Struct(ref fields) => {
+                let fields = schema::fields_schema(fields);
+                quote! { __argley::SchemaKind::Struct(#fields) }
+            }
+            Inner::Enum(ref variants) => {
+                let variants = variants
+                    .iter()
+                    .filter(move |v| !v.opts.skip)
+                    .map(schema::variant_schema);
+                quote! { __argley::SchemaKind::Enum(&[#(#variants),*]) }
+            }
+        }
+    }
+
+    pub fn container_opts(&self) -> &Rc<ContainerOpts> {
+        &self.container_opts
+    }
+
+    pub fn new_empty(opts: ContainerOpts, data: &Data) -> syn::Result<Self> {
+        Self::new(opts, Inner::new_empty(data)?)
+    }
+
+    fn render_empty(&self, tokens: &mut TokenStream) {
+        let fallible = self.container_opts.fallible;
+        FunctionSignature::without_consumer(true)
+            .fallible(fallible)
+            .to_tokens(tokens);
+
+        let body = if fallible {
+            quote! { ::std::result::Result::Ok(false) }
+        } else {
+            quote! { false }
+        };
+        Group::new(Delimiter::Brace, body).to_tokens(tokens);
+    }
+
+    /// `add_to` override for containers that drop the name & enums with variants that drop or
+    /// skip it
+    pub fn named_impl(&self) -> Option<TokenStream> {
+        let fallible = self.container_opts.fallible;
+        let consumer = new_ident(ARG_CONSUMER);
+        let name = new_ident("name");
+
+        let (unnamed, skipped) = if fallible {
+            (
+                quote! { __argley::TryArg::try_add_unnamed_to(self, #consumer) },
+                quote! { ::std::result::Result::Ok(false) },
+            )
+        } else {
+            (
+                quote! { __argley::Arg::add_unnamed_to(self, #consumer) },
+                quote! { false },
+            )
+        };
+        let signature = |name: &Ident| {
+            if fallible {
+                quote! { fn try_add_to(&self, #name: &str, #consumer: &mut impl __argley::ArgConsumer) -> ::std::result::Result<bool, __argley::ArgError> }
+            } else {
+                quote! { fn add_to(&self, #name: &str, #consumer: &mut impl __argley::ArgConsumer) -> bool }
+            }
+        };
+
+        if self.container_opts.drop_name {
+            let signature = signature(&new_ident("_"));
+            return Some(quote! {
+                #[inline]
+                #signature {
+                    #unnamed
+                }
+            });
+        }
+
+        let variants = match self.inner {
+            Inner::Enum(ref variants) => variants,
+            Inner::Struct(_) => return None,
+        };
+
+        let mut arms = TokenStream::new();
+        let mut has_default_arm = false;
+        for variant in variants {
+            let ident = &variant.ident;
+            if variant.opts.skip {
+                arms.append_all(quote! { Self::#ident { .. } => #skipped, });
+            } else if variant.opts.drop_name {
+                arms.append_all(quote! { Self::#ident { .. } => #unnamed, });
+            } else {
+                has_default_arm = true;
+            }
+        }
+
+        if arms.is_empty() {
+            return None;
+        }
+        if has_default_arm {
+            arms.append_all(quote! {
+                _ => {
+                    __argley::ArgConsumer::add_name(#consumer, #name);
+                    #unnamed
+                }
+            });
+        }
+
+        let signature = signature(&name);
+        Some(quote! {
+            #signature {
+                match *self {
+                    #arms
+                }
+            }
         })
     }
 
-    fn render_empty(tokens: &mut TokenStream) {
-        FunctionSignature::without_consumer(true).to_tokens(tokens);
-        Group::new(Delimiter::Brace, quote! { false }).to_tokens(tokens);
+    pub fn from_data(opts: ContainerOpts, data: Data) -> syn::Result<Self> {
+        Self::new(opts, data.try_into()?)
     }
 
-    pub fn from_data(opts: Rc<ContainerOpts>, data: Data) -> syn::Result<Self> {
+    fn new(opts: ContainerOpts, mut inner: Inner) -> syn::Result<Self> {
+        if let Some(naming) = opts.variant_naming() {
+            let variants = match inner {
+                Inner::Enum(ref mut variants) => variants,
+                Inner::Struct(_) => {
+                    return Err(syn::Error::new(
+                        naming.span(),
+                        format!("`{naming}` is only supported on enums"),
+                    ));
+                }
+            };
+
+            let rule = opts.rename_all.unwrap_or_default();
+            for variant in variants {
+                if opts.value_enum.is_some() {
+                    variant.default_unit_value(rule);
+                } else if opts.flag_enum.is_some() {
+                    variant.set_name(rule, Some(opts.short));
+                } else {
+                    variant.set_name(rule, None);
+                }
+            }
+        } else if let Inner::Enum(ref variants) = inner {
+            if let Some(rename) = variants.iter().find_map(move |v| v.opts.rename.as_ref()) {
+                return Err(syn::Error::new(
+                    rename.span(),
+                    "Variant `rename` requires `value_enum`, `subcommand` or `flag_enum` on the enum",
+                ));
+            }
+        }
+
+        let constraints = match inner {
+            Inner::Struct(ref fields) => Constraints::collect(fields, &opts)?,
+            Inner::Enum(ref variants) => {
+                let constrained = variants
+                    .iter()
+                    .flat_map(move |v| v.fields.iter())
+                    .any(move |f| f.opts.has_constraints());
+
+                if constrained || !opts.required_one_of.is_empty() {
+                    return Err(syn::Error::new(
+                        Span::call_site(),
+                        "Cross-field constraints are only supported on structs",
+                    ));
+                }
+
+                Constraints::default()
+            }
+        };
+
+        if opts.fallible {
+            inner.make_fallible();
+        } else {
+            let span = inner.fallible_span().or_else(|| {
+                opts.required_one_of
+                    .iter()
+                    .flatten()
+                    .next()
+                    .map(LitStr::span)
+            });
+
+            if let Some(span) = span {
+                return Err(syn::Error::new(
+                    span,
+                    "Validators, constraints & `path` can fail; add `#[arg(fallible)]` to the container",
+                ));
+            }
+        }
+
         Ok(Self {
-            inner: data.try_into()?,
-            container_opts: opts,
+            inner,
+            constraints,
+            container_opts: Rc::new(opts),
         })
     }
 }
@@ -48,38 +280,39 @@ impl ParsedFields {
 impl ToTokens for ParsedFields {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         if self.is_empty() {
-            return Self::render_empty(tokens);
+            return self.render_empty(tokens);
         }
 
-        FunctionSignature::with_consumer(false).to_tokens(tokens);
+        let fallible = self.container_opts.fallible;
+        FunctionSignature::with_consumer(false)
+            .fallible(fallible)
+            .to_tokens(tokens);
 
         let body = {
             let mut tokens = TokenStream::new();
+            let constraints = &self.constraints;
 
             match self.container_opts.static_args {
                 Some(ref args) if !args.elems.is_empty() => {
-                    let consumer = new_ident(ARG_CONSUMER);
-
-                    if args.elems.len() == 1 {
-                        let first_arg = &args.elems[0];
-                        tokens.append_all(quote! {
-                            ::argley::ArgConsumer::add_arg(#consumer, #first_arg);
-                        });
-                    } else {
-                        let group = args.to_token_stream();
-                        tokens.append_all(quote! {
-                            ::argley::ArgConsumer::add_args(#consumer, #group);
-                        });
-                    }
-
-                    self.inner.to_tokens(Some(new_ident("true")), &mut tokens);
+                    tokens.append_all(add_static_args(args));
+                    self.inner.to_tokens(
+                        Some(new_ident("true")),
+                        fallible,
+                        constraints,
+                        &mut tokens,
+                    );
                 }
                 _ => {
-                    self.inner.to_tokens(None, &mut tokens);
+                    self.inner
+                        .to_tokens(None, fallible, constraints, &mut tokens);
                 }
             };
 
-            tokens
+            if constraints.is_empty() {
+                tokens
+            } else {
+                Constraints::buffered(tokens)
+            }
         };
 
         Group::new(Delimiter::Brace, body).to_tokens(tokens);
@@ -95,12 +328,7 @@ impl Inner {
     fn is_empty(&self) -> bool {
         match self {
             Self::Struct(fields) => fields.is_empty(),
-            Self::Enum(variants) => {
-                variants.is_empty()
-                    || variants.iter().all(move |v| {
-                        matches!(v.fields, TypedFields::Unit) && v.unit_variant_value.is_none()
-                    })
-            }
+            Self::Enum(variants) => variants.iter().all(ParsedVariant::is_empty),
         }
     }
 
@@ -113,26 +341,81 @@ impl Inner {
         })
     }
 
-    fn to_tokens(&self, return_value: Option<Ident>, tokens: &mut TokenStream) {
-        if let Some(return_value) = return_value {
-            self.to_tokens_base(false, tokens);
-            tokens.append(return_value);
+    /// Span of the first field option that can only be emitted through `TryArg`
+    fn fallible_span(&self) -> Option<Span> {
+        match self {
+            Self::Struct(fields) => fields.iter().find_map(move |f| f.opts.fallible_span()),
+            Self::Enum(variants) => variants
+                .iter()
+                .flat_map(move |v| v.fields.iter())
+                .find_map(move |f| f.opts.fallible_span()),
+        }
+    }
+
+    fn make_fallible(&mut self) {
+        match self {
+            Self::Struct(fields) => {
+                for field in fields {
+                    field.fallible = true;
+                }
+            }
+            Self::Enum(variants) => {
+                for variant in variants {
+                    variant.make_fallible();
+                }
+            }
+        }
+    }
+
+    fn to_tokens(
+        &self,
+        return_value: Option<Ident>,
+        fallible: bool,
+        constraints: &Constraints,
+        tokens: &mut TokenStream,
+    ) {
+        let return_value = if let Some(return_value) = return_value {
+            self.to_tokens_base(false, constraints, tokens);
+            return_value
         } else {
             let any_added = new_ident(PROP_ANY_ADDED);
             tokens.append_all(quote! { let mut #any_added = false; });
-            self.to_tokens_base(true, tokens);
+            self.to_tokens_base(true, constraints, tokens);
 
-            tokens.append(any_added);
+            any_added
         };
+        constraints.to_tokens(tokens);
+
+        if fallible {
+            tokens.append_all(quote! { ::std::result::Result::Ok(#return_value) });
+        } else {
+            tokens.append(return_value);
+        }
     }
 
-    fn to_tokens_base(&self, use_any_added: bool, tokens: &mut TokenStream) {
+    /// Fields referenced by `constraints` store whether they got emitted in their flag
+    fn to_tokens_base(
+        &self,
+        use_any_added: bool,
+        constraints: &Constraints,
+        tokens: &mut TokenStream,
+    ) {
         match self {
             Self::Struct(fields) => {
-                if use_any_added {
-                    tokens.append_all(fields.iter().map(AnyAddedWrapper));
-                } else {
-                    tokens.append_terminated(fields, Punct::new(';', Spacing::Joint));
+                for field in fields {
+                    match constraints.flag(&field.label) {
+                        Some(flag) => {
+                            tokens.append_all(quote! { let #flag = #field; });
+                            if use_any_added {
+                                AnyAddedWrapper(&flag).to_tokens(tokens);
+                            }
+                        }
+                        None if use_any_added => AnyAddedWrapper(field).to_tokens(tokens),
+                        None => {
+                            field.to_tokens(tokens);
+                            tokens.append(Punct::new(';', Spacing::Joint));
+                        }
+                    }
                 }
             }
             Self::Enum(variants) => {
@@ -174,12 +457,19 @@ impl TryFrom<Data> for Inner {
     }
 }
 
+/// Statement adding the given `static_args` to the consumer
+pub fn add_static_args(args: &ExprArray) -> TokenStream {
+    let consumer = new_ident(ARG_CONSUMER);
+    quote! { __argley::ArgConsumer::add_static_args(#consumer, #args); }
+}
+
 fn on_union(un: &impl Spanned) -> syn::Error {
     syn::Error::new(un.span(), "Unions not supported")
 }
 
 pub struct FunctionSignature<'a> {
     inline: bool,
+    fallible: bool,
     consumer_arg: &'a str,
 }
 
@@ -188,6 +478,7 @@ impl<'a> FunctionSignature<'a> {
     pub fn with_consumer(inline: bool) -> Self {
         Self {
             inline,
+            fallible: false,
             consumer_arg: ARG_CONSUMER,
         }
     }
@@ -196,9 +487,17 @@ impl<'a> FunctionSignature<'a> {
     pub fn without_consumer(inline: bool) -> Self {
         Self {
             inline,
+            fallible: false,
             consumer_arg: "_",
         }
     }
+
+    /// Render `TryArg::try_add_unnamed_to` instead of `Arg::add_unnamed_to`
+    #[inline]
+    pub fn fallible(mut self, fallible: bool) -> Self {
+        self.fallible = fallible;
+        self
+    }
 }
 
 impl ToTokens for FunctionSignature<'_> {
@@ -208,8 +507,14 @@ impl ToTokens for FunctionSignature<'_> {
         }
 
         let consumer = new_ident(self.consumer_arg);
-        tokens.append_all(quote! {
-            fn add_unnamed_to(&self, #consumer: &mut impl ::argley::ArgConsumer) -> bool
+        tokens.append_all(if self.fallible {
+            quote! {
+                fn try_add_unnamed_to(&self, #consumer: &mut impl __argley::ArgConsumer) -> ::std::result::Result<bool, __argley::ArgError>
+            }
+        } else {
+            quote! {
+                fn add_unnamed_to(&self, #consumer: &mut impl __argley::ArgConsumer) -> bool
+            }
         });
     }
 }
@@ -2,46 +2,147 @@ use std::iter;
 
 use proc_macro2::{Delimiter, Group, Ident, Punct, Spacing, TokenStream};
 use quote::{quote, ToTokens, TokenStreamExt};
-use syn::{token, Expr, Token, Variant};
+use syn::{parse_quote, token, LitStr, Variant};
 
 use crate::any_added_wrap::AnyAddedWrapper;
+use crate::parsed_fields::add_static_args;
+use crate::rename_rule::RenameRule;
 use crate::struct_field::{StructField, TypedFields};
-use crate::{new_ident, TryCollectStable, ARG_CONSUMER, ATTR};
+use crate::variant_opts::VariantOpts;
+use crate::{doc_comment, new_ident, TryCollectStable, ARG_CONSUMER, ATTR, PROP_ANY_ADDED};
 
 pub struct ParsedVariant {
     pub ident: Ident,
     pub fields: TypedFields,
-    pub unit_variant_value: Option<Expr>,
+    pub opts: VariantOpts,
+
+    /// Subcommand or flag name output before the variant's fields
+    pub name: Option<LitStr>,
+    pub fallible: bool,
+
+    /// The variant's doc comment
+    pub doc: Option<String>,
 }
 
 impl ParsedVariant {
-    fn append_unit(&self, use_any_added: bool, tokens: &mut TokenStream) {
-        let body = if let Some(ref val) = self.unit_variant_value {
+    pub fn make_fallible(&mut self) {
+        self.fallible = true;
+        for field in self.fields.iter_mut() {
+            field.fallible = true;
+        }
+    }
+
+    /// Whether the variant never outputs anything
+    pub fn is_empty(&self) -> bool {
+        self.opts.skip
+            || (matches!(self.fields, TypedFields::Unit)
+                && self.opts.value.is_none()
+                && self.opts.static_args.is_none()
+                && self.name.is_none())
+    }
+
+    /// The variant's `rename`, or its name converted according to `rule`
+    pub fn renamed(&self, rule: RenameRule) -> LitStr {
+        match self.opts.rename {
+            Some(ref rename) => rename.clone(),
+            None => LitStr::new(&rule.apply(&self.ident.to_string()), self.ident.span()),
+        }
+    }
+
+    /// Make a unit variant without a `value` output its name, converted according to `rule`
+    pub fn default_unit_value(&mut self, rule: RenameRule) {
+        if matches!(self.fields, TypedFields::Unit) && self.opts.value.is_none() {
+            let name = self.renamed(rule);
+            self.opts.value = Some(parse_quote!(#name));
+        }
+    }
+
+    /// Make the variant output its name, converted according to `rule`, before its fields. With
+    /// `flag` set the name gets prefixed with `--`, or `-` if either the container (`flag`'s
+    /// value) or the variant is `short`.
+    pub fn set_name(&mut self, rule: RenameRule, flag: Option<bool>) {
+        let name = self.renamed(rule);
+        self.name = Some(match flag {
+            Some(short) => {
+                let prefix = if short || self.opts.short { "-" } else { "--" };
+                LitStr::new(&format!("{prefix}{}", name.value()), name.span())
+            }
+            None => name,
+        });
+    }
+
+    /// Statements reporting the variant to the consumer & outputting its subcommand/flag name &
+    /// static args, if any
+    fn head(&self, use_any_added: bool) -> TokenStream {
+        let consumer = new_ident(ARG_CONSUMER);
+        let label = LitStr::new(&self.ident.to_string(), self.ident.span());
+        let name = match self.name {
+            Some(ref name) => quote! { ::std::option::Option::Some(#name) },
+            None => quote! { ::std::option::Option::None },
+        };
+        let mut head = quote! { __argley::ArgConsumer::enter_variant(#consumer, #label, #name); };
+        let mut outputs = false;
+
+        if let Some(ref name) = self.name {
+            head.append_all(quote! { __argley::ArgConsumer::add_name(#consumer, #name); });
+            outputs = true;
+        }
+        match self.opts.static_args {
+            Some(ref args) if !args.elems.is_empty() => {
+                head.append_all(add_static_args(args));
+                outputs = true;
+            }
+            _ => {}
+        }
+
+        if use_any_added && outputs {
+            let any_added = new_ident(PROP_ANY_ADDED);
+            head.append_all(quote! { #any_added = true; });
+        }
+
+        head
+    }
+
+    fn append_unit(&self, use_any_added: bool, mut body: TokenStream, tokens: &mut TokenStream) {
+        if let Some(ref val) = self.opts.value {
             let mut base = {
                 let consumer = new_ident(ARG_CONSUMER);
 
-                quote! { ::argley::Arg::add_unnamed_to(#val, #consumer) }
+                if self.fallible {
+                    quote! { __argley::TryArg::try_add_unnamed_to(#val, #consumer)? }
+                } else {
+                    quote! { __argley::Arg::add_unnamed_to(#val, #consumer) }
+                }
             };
 
             if use_any_added {
-                AnyAddedWrapper(&base).into_token_stream()
+                body.append_all(AnyAddedWrapper(&base).into_token_stream());
             } else {
                 base.append(Punct::new(';', Spacing::Joint));
-                base
+                body.append_all(base);
             }
-        } else {
-            TokenStream::new()
-        };
+        }
 
         Self::append_body(tokens, body);
     }
 
-    fn append_tuple(fields: &[StructField], use_any_added: bool, tokens: &mut TokenStream) {
+    fn append_tuple(
+        fields: &[StructField],
+        len: usize,
+        use_any_added: bool,
+        head: TokenStream,
+        tokens: &mut TokenStream,
+    ) {
         // Self::Variant(THIS_PART) =>
         tokens.append({
-            let inner_iter = fields.iter().map(move |f| {
-                let id = &f.ident;
-                quote! { ref #id }
+            // Skipped fields are absent from `fields`; match them with `_`
+            let inner_iter = (0..len).map(move |idx| {
+                if let Some(f) = fields.iter().find(move |f| f.idx == idx) {
+                    let id = &f.ident;
+                    quote! { ref #id }
+                } else {
+                    quote! { _ }
+                }
             });
 
             let mut inner_stream = TokenStream::new();
@@ -50,7 +151,7 @@ impl ParsedVariant {
             Group::new(Delimiter::Parenthesis, inner_stream)
         });
 
-        let mut body = TokenStream::new();
+        let mut body = head;
 
         if use_any_added {
             let mut fields = fields.iter().map(AnyAddedWrapper).collect::<Vec<_>>();
@@ -71,6 +172,7 @@ impl ParsedVariant {
         fields: &Vec<StructField>,
         has_skips: bool,
         use_any_added: bool,
+        head: TokenStream,
         tokens: &mut TokenStream,
     ) {
         // Self::Variant { THIS_PART } =>
@@ -92,7 +194,7 @@ impl ParsedVariant {
             Group::new(Delimiter::Brace, inner_stream)
         });
 
-        let mut body = TokenStream::new();
+        let mut body = head;
 
         if use_any_added {
             body.append_all(fields.iter().map(AnyAddedWrapper));
@@ -103,7 +205,11 @@ impl ParsedVariant {
         Self::append_body(tokens, body);
     }
 
-    fn append_body(tokens: &mut TokenStream, body: TokenStream) {
+    /// Append `=> { body }`, reporting the end of the variant to the consumer
+    fn append_body(tokens: &mut TokenStream, mut body: TokenStream) {
+        let consumer = new_ident(ARG_CONSUMER);
+        body.append_all(quote! { __argley::ArgConsumer::exit_variant(#consumer); });
+
         token::FatArrow::default().to_tokens(tokens);
         tokens.append(Group::new(Delimiter::Brace, body));
     }
@@ -113,18 +219,24 @@ impl ParsedVariant {
         tokens.append_all(quote! { Self:: });
         self.ident.to_tokens(&mut tokens);
 
+        if self.opts.skip {
+            tokens.append_all(quote! { { .. } => {} });
+            return tokens;
+        }
+
+        let head = self.head(use_any_added);
         match self.fields {
             TypedFields::Unit => {
-                self.append_unit(use_any_added, &mut tokens);
+                self.append_unit(use_any_added, head, &mut tokens);
             }
-            TypedFields::Tuple(ref fields) => {
-                Self::append_tuple(fields, use_any_added, &mut tokens);
+            TypedFields::Tuple { ref fields, len } => {
+                Self::append_tuple(fields, len, use_any_added, head, &mut tokens);
             }
             TypedFields::Named {
                 ref fields,
                 has_skips,
             } => {
-                Self::append_fields(fields, has_skips, use_any_added, &mut tokens);
+                Self::append_fields(fields, has_skips, use_any_added, head, &mut tokens);
             }
         };
 
@@ -136,40 +248,35 @@ impl TryFrom<Variant> for ParsedVariant {
     type Error = syn::Error;
 
     fn try_from(variant: Variant) -> Result<Self, Self::Error> {
-        if let Some((_, disc)) = variant.discriminant {
-            return Err(syn::Error::new_spanned(disc, "Discriminants not supported"));
+        let doc = doc_comment(&variant.attrs);
+        let opts = variant
+            .attrs
+            .into_iter()
+            .filter_map(move |attr| {
+                if attr.path().is_ident(ATTR) {
+                    Some(VariantOpts::try_from(attr))
+                } else {
+                    None
+                }
+            })
+            .try_collect()?
+            .into_iter()
+            .sum::<VariantOpts>();
+
+        let mut fields = StructField::collect_from_fields(variant.fields, false)?;
+        if opts.short {
+            for field in fields.iter_mut() {
+                field.opts.short = Some(new_ident("short"));
+            }
         }
 
-        let fields = StructField::collect_from_fields(variant.fields, false)?;
-
         Ok(Self {
             ident: variant.ident,
             fields,
-            unit_variant_value: variant
-                .attrs
-                .into_iter()
-                .filter_map(move |attr| {
-                    if !attr.path().is_ident(ATTR) {
-                        return None;
-                    }
-
-                    let mut unit_variant_value = None;
-                    let result = attr.parse_nested_meta(|meta| {
-                        if !meta.path.is_ident("value") {
-                            return Err(syn::Error::new_spanned(meta.path, "Unrecognised option"));
-                        }
-
-                        meta.input.parse::<Token![=]>()?;
-                        unit_variant_value = Some(meta.input.parse::<Expr>()?);
-
-                        Ok(())
-                    });
-
-                    Some(result.map(move |()| unit_variant_value))
-                })
-                .try_collect()?
-                .into_iter()
-                .fold(None, move |acc, val| if val.is_some() { val } else { acc }),
+            opts,
+            name: None,
+            fallible: false,
+            doc,
         })
     }
 }
@@ -0,0 +1,70 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::{parenthesized, Expr, LitStr};
+
+/// Parsed `#[arg(path = "...")]` option
+pub struct PathMode {
+    span: Span,
+    kind: PathModeKind,
+}
+
+enum PathModeKind {
+    ExpandHome,
+    Absolute,
+    Canonical,
+    RelativeTo(Box<Expr>),
+}
+
+impl PathMode {
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl Parse for PathMode {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let lit = input.parse::<LitStr>()?;
+
+        Ok(Self {
+            span: lit.span(),
+            kind: lit.parse_with(PathModeKind::parse)?,
+        })
+    }
+}
+
+impl PathModeKind {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident = input.parse::<Ident>()?;
+
+        Ok(match ident.to_string().as_str() {
+            "expand_home" => Self::ExpandHome,
+            "absolute" => Self::Absolute,
+            "canonical" => Self::Canonical,
+            "relative_to" => {
+                let content;
+                parenthesized!(content in input);
+                Self::RelativeTo(content.parse()?)
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    "Expected one of `expand_home`, `absolute`, `canonical`, `relative_to(EXPRESSION)`",
+                ));
+            }
+        })
+    }
+}
+
+impl ToTokens for PathMode {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(match self.kind {
+            PathModeKind::ExpandHome => quote! { __argley::PathMode::ExpandHome },
+            PathModeKind::Absolute => quote! { __argley::PathMode::Absolute },
+            PathModeKind::Canonical => quote! { __argley::PathMode::Canonical },
+            PathModeKind::RelativeTo(ref base) => {
+                quote_spanned! { self.span=> __argley::PathMode::relative_to(&#base) }
+            }
+        });
+    }
+}
@@ -0,0 +1,90 @@
+use syn::parse::{Parse, ParseStream};
+use syn::LitStr;
+
+/// Case conversion applied to variant names, following serde's `rename_all` naming
+#[derive(Copy, Clone)]
+pub enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    const VARIANTS: [(&'static str, Self); 8] = [
+        ("lowercase", Self::Lower),
+        ("UPPERCASE", Self::Upper),
+        ("PascalCase", Self::Pascal),
+        ("camelCase", Self::Camel),
+        ("snake_case", Self::Snake),
+        ("SCREAMING_SNAKE_CASE", Self::ScreamingSnake),
+        ("kebab-case", Self::Kebab),
+        ("SCREAMING-KEBAB-CASE", Self::ScreamingKebab),
+    ];
+
+    /// Convert a `PascalCase` variant name
+    pub fn apply(self, variant: &str) -> String {
+        match self {
+            Self::Lower => variant.to_ascii_lowercase(),
+            Self::Upper => variant.to_ascii_uppercase(),
+            Self::Pascal => variant.to_owned(),
+            Self::Camel => {
+                let mut chars = variant.chars();
+                match chars.next() {
+                    Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                    None => String::new(),
+                }
+            }
+            Self::Snake => separate(variant, '_'),
+            Self::ScreamingSnake => separate(variant, '_').to_ascii_uppercase(),
+            Self::Kebab => separate(variant, '-'),
+            Self::ScreamingKebab => separate(variant, '-').to_ascii_uppercase(),
+        }
+    }
+}
+
+impl Default for RenameRule {
+    #[inline]
+    fn default() -> Self {
+        Self::Kebab
+    }
+}
+
+impl Parse for RenameRule {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let lit = input.parse::<LitStr>()?;
+        let value = lit.value();
+
+        match Self::VARIANTS.iter().find(move |(name, _)| *name == value) {
+            Some(&(_, rule)) => Ok(rule),
+            None => {
+                let expected = Self::VARIANTS
+                    .iter()
+                    .map(move |(name, _)| format!("\"{name}\""))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                Err(syn::Error::new(
+                    lit.span(),
+                    format!("Unknown rename rule. Expected one of {expected}"),
+                ))
+            }
+        }
+    }
+}
+
+/// Lowercase the name, separating words with `separator`
+fn separate(variant: &str, separator: char) -> String {
+    let mut out = String::with_capacity(variant.len() + 4);
+    for (idx, ch) in variant.char_indices() {
+        if idx != 0 && ch.is_uppercase() {
+            out.push(separator);
+        }
+        out.push(ch.to_ascii_lowercase());
+    }
+    out
+}
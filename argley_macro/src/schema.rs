@@ -0,0 +1,145 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::Type;
+
+use crate::parsed_variant::ParsedVariant;
+use crate::struct_field::StructField;
+
+/// `&[FieldSchema]` expression describing the fields in declaration order
+pub fn fields_schema<'a>(fields: impl IntoIterator<Item = &'a StructField>) -> TokenStream {
+    let mut fields = fields.into_iter().collect::<Vec<_>>();
+    fields.sort_by_key(move |f| f.idx);
+
+    let fields = fields.into_iter().map(field_schema);
+    quote! { &[#(#fields),*] }
+}
+
+/// `VariantSchema` expression
+pub fn variant_schema(variant: &ParsedVariant) -> TokenStream {
+    let label = variant.ident.to_string();
+    let name = option(variant.name.as_ref());
+    let fields = fields_schema(variant.fields.iter());
+    let doc = option(variant.doc.as_ref());
+
+    quote! {
+        __argley::VariantSchema {
+            label: #label,
+            name: #name,
+            fields: #fields,
+            doc: #doc,
+        }
+    }
+}
+
+fn field_schema(field: &StructField) -> TokenStream {
+    let opts = &field.opts;
+    let kind = if opts.subcommand.is_some() {
+        quote! { Nested }
+    } else if opts.variadic.is_some() {
+        quote! { Variadic }
+    } else if opts.position.is_some() {
+        quote! { Positional }
+    } else if is_bool(&field.ty) {
+        quote! { Flag }
+    } else {
+        quote! { Option }
+    };
+
+    let label = &field.label;
+    let name = match field.arg_name() {
+        Some(name) => {
+            let drops_name = drops_name(field);
+            quote! { __argley::FieldSchema::kept_name(#name, #drops_name) }
+        }
+        None => quote! { ::std::option::Option::None },
+    };
+    let position = option(opts.position.as_ref());
+    let type_name = type_name(&field.ty);
+    let optional = optional(field);
+    let value_name = option(opts.value_name.as_ref());
+    let doc = option(field.doc.as_ref());
+
+    quote! {
+        __argley::FieldSchema::new(
+            #label,
+            #name,
+            __argley::FieldKind::#kind,
+            #position,
+            #type_name,
+            #optional,
+            #value_name,
+            #doc,
+        )
+    }
+}
+
+fn option(value: Option<&impl ToTokens>) -> TokenStream {
+    match value {
+        Some(value) => quote! { ::std::option::Option::Some(#value) },
+        None => quote! { ::std::option::Option::None },
+    }
+}
+
+/// Whether the field may be absent from the output: its type's `Arg::OPTIONAL` or
+/// `TryArg::OPTIONAL` unless it's `required`. Needs `NotArg` & `NotTryArg` in scope for types
+/// that don't implement the traits. The probes get combined with `|` as `||` branches, which
+/// stops the schema from being promoted to a `'static`.
+fn optional(field: &StructField) -> TokenStream {
+    if field.opts.required.is_some() || field.opts.subcommand.is_some() {
+        quote! { false }
+    } else {
+        let ty = &field.ty;
+        quote! { (<__argley::ArgProbe<#ty>>::OPTIONAL | <__argley::TryArgProbe<#ty>>::OPTIONAL) }
+    }
+}
+
+/// Whether the field's type ignores the name it gets passed, per its `Arg::DROPS_NAME` or
+/// `TryArg::DROPS_NAME`. Fields rendered through a `formatter`, `with` module or format
+/// specification are assumed to keep it. Needs `NotArg` & `NotTryArg` in scope.
+pub fn drops_name(field: &StructField) -> TokenStream {
+    let opts = &field.opts;
+    if opts.formatter.is_some() || opts.with.is_some() || opts.format_spec().is_some() {
+        quote! { false }
+    } else {
+        let ty = &field.ty;
+        quote! { (<__argley::ArgProbe<#ty>>::DROPS_NAME | <__argley::TryArgProbe<#ty>>::DROPS_NAME) }
+    }
+}
+
+fn is_bool(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path.qself.is_none() && path.path.is_ident("bool"),
+        _ => false,
+    }
+}
+
+/// The type as written in the source, without the spacing `TokenStream`'s `Display` adds
+pub fn type_name(ty: &Type) -> String {
+    let mut out = String::new();
+    let tokens = ty.to_token_stream().to_string();
+    let mut chars = tokens.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != ' ' {
+            out.push(ch);
+            continue;
+        }
+
+        let prev = out.chars().last();
+        let next = chars.peek().copied();
+        let keep = match (prev, next) {
+            (Some(',' | ';' | '='), _) | (_, Some('=')) => true,
+            (Some(prev), Some(next)) => is_word(prev) && is_word(next),
+            _ => false,
+        };
+        if keep {
+            out.push(' ');
+        }
+    }
+
+    out
+}
+
+fn is_word(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_' || ch == '\''
+}
@@ -1,25 +1,40 @@
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::iter::Enumerate;
 
 use proc_macro2::{Literal, Span, TokenStream};
 use quote::{quote, ToTokens, TokenStreamExt};
-use syn::{Attribute, Field, Fields};
+use syn::spanned::Spanned;
+use syn::{Attribute, Field, Fields, Path, Type};
 
 use crate::field_ident::FieldIdent;
 use crate::field_opts::FieldOpts;
-use crate::{new_ident, TryCollectStable, ARG_CONSUMER, ATTR};
+use crate::{doc_comment, new_ident, TryCollectStable, ARG_CONSUMER, ATTR, FORMATTED_VALUE};
 
 pub struct StructField {
     pub opts: FieldOpts,
     pub idx: usize,
     pub is_struct: bool,
+    pub fallible: bool,
+
+    /// The field's name in the source, or its index for tuple fields
+    pub label: String,
 
     pub ident: FieldIdent,
+    pub ty: Type,
+
+    /// The field's doc comment
+    pub doc: Option<String>,
 }
 
 pub enum TypedFields {
     Unit,
-    Tuple(Vec<StructField>),
+    Tuple {
+        fields: Vec<StructField>,
+
+        /// Number of fields including skipped ones
+        len: usize,
+    },
     Named {
         fields: Vec<StructField>,
         has_skips: bool,
@@ -29,12 +44,28 @@ pub enum TypedFields {
 impl From<TypedFields> for Vec<StructField> {
     fn from(value: TypedFields) -> Self {
         match value {
-            TypedFields::Tuple(fields) | TypedFields::Named { fields, .. } => fields,
+            TypedFields::Tuple { fields, .. } | TypedFields::Named { fields, .. } => fields,
             TypedFields::Unit => Vec::new(),
         }
     }
 }
 
+impl TypedFields {
+    pub fn iter(&self) -> std::slice::Iter<'_, StructField> {
+        match self {
+            Self::Tuple { fields, .. } | Self::Named { fields, .. } => fields.iter(),
+            Self::Unit => [].iter(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, StructField> {
+        match self {
+            Self::Tuple { fields, .. } | Self::Named { fields, .. } => fields.iter_mut(),
+            Self::Unit => [].iter_mut(),
+        }
+    }
+}
+
 impl StructField {
     pub fn cmp(&self, other: &Self) -> Ordering {
         match self.opts.partial_cmp(&other.opts) {
@@ -50,6 +81,7 @@ impl StructField {
             Fields::Unit => return Ok(TypedFields::Unit),
         };
 
+        let len = fields.len();
         let (has_skips, mut fields) = if fields.is_empty() {
             (false, Vec::new())
         } else {
@@ -63,13 +95,7 @@ impl StructField {
             }
             TypedFields::Named { has_skips, fields }
         } else {
-            if has_skips && !is_struct {
-                return Err(syn::Error::new(
-                    Span::call_site(),
-                    "Tuple struct fields may not be skipped",
-                ));
-            }
-            TypedFields::Tuple(fields)
+            TypedFields::Tuple { fields, len }
         })
     }
 
@@ -81,6 +107,18 @@ impl StructField {
 
         let mut fields = FieldFilterMapper::new(is_struct, &mut has_skips, fields).try_collect()?;
 
+        let has_subcommand = fields.iter().any(move |f| f.opts.subcommand.is_some());
+        if !has_subcommand {
+            if let Some(global) = fields.iter().find_map(move |f| f.opts.global.as_ref()) {
+                return Err(syn::Error::new(
+                    global.span(),
+                    "`global` requires a `subcommand` field alongside it",
+                ));
+            }
+        }
+
+        check_duplicates(&fields)?;
+
         if is_struct {
             fields.sort_by(StructField::cmp);
         }
@@ -89,54 +127,210 @@ impl StructField {
     }
 }
 
+impl StructField {
+    /// Expression evaluating to a reference to the field
+    fn field_expr_base(&self) -> TokenStream {
+        let ident = &self.ident;
+        if self.is_struct {
+            quote! { &self.#ident }
+        } else {
+            ident.to_token_stream()
+        }
+    }
+
+    /// The name the field gets output with; `None` for positional, variadic & subcommand fields
+    pub fn arg_name(&self) -> Option<Literal> {
+        let opts = &self.opts;
+        if opts.position.is_some() || opts.variadic.is_some() || opts.subcommand.is_some() {
+            return None;
+        }
+
+        let mut name = opts.name_prefix();
+        let span = if let Some(rename) = &opts.rename {
+            let rename_str = rename.to_string();
+            name.push_str(&rename_str[1..rename_str.len() - 1]);
+            rename.span()
+        } else {
+            name.push_str(&self.ident.to_string());
+            match self.ident {
+                FieldIdent::Ident(ref ident) => ident.span(),
+                FieldIdent::Idx(_) => Span::call_site(),
+            }
+        };
+        let mut name = Literal::string(&name);
+        name.set_span(span);
+
+        Some(name)
+    }
+
+    /// Call to the `add_to`/`add_unnamed_to` function of a `with` module
+    fn with_call(&self, with: &Path, field_expr: &TokenStream) -> TokenStream {
+        let consumer = new_ident(ARG_CONSUMER);
+        if let Some(name) = self.arg_name() {
+            quote! { #with::add_to(#field_expr, #name, #consumer) }
+        } else {
+            quote! { #with::add_unnamed_to(#field_expr, #consumer) }
+        }
+    }
+
+    fn to_tokens_fallible(&self, tokens: &mut TokenStream) {
+        let opts = &self.opts;
+        let consumer = new_ident(ARG_CONSUMER);
+        let name = self.arg_name();
+        let add = move |field_expr: TokenStream| {
+            let call = if let Some(ref name) = name {
+                quote! { add_field_to(#name, #consumer) }
+            } else {
+                quote! { add_field_unnamed_to(#consumer) }
+            };
+
+            quote! {
+                {
+                    use __argley::{AddViaArg as _, AddViaTryArg as _};
+                    (&__argley::FieldArg(#field_expr)).#call
+                }
+            }
+        };
+
+        let field_expr_base = self.field_expr_base();
+        let validators = opts.validators(&field_expr_base);
+        let result = if let Some(formatted) = opts.formatter_call(&field_expr_base) {
+            let value = new_ident(FORMATTED_VALUE);
+            let add = add(quote! { &#value });
+
+            quote! {
+                __argley::IntoTryArg::into_try_arg(#formatted)
+                    .and_then(|#value| #add)
+            }
+        } else if let Some(with) = &opts.with {
+            let call = self.with_call(with, &field_expr_base);
+            quote! { __argley::IntoArgResult::into_arg_result(#call) }
+        } else if let Some(format_spec) = opts.format_spec() {
+            add(quote! { &__argley::Formatted::new(#field_expr_base, #format_spec) })
+        } else {
+            add(field_expr_base)
+        };
+
+        let result = validators
+            .into_iter()
+            .rev()
+            .fold(result, move |result, validator| {
+                quote! { #validator.and_then(|()| #result) }
+            });
+
+        let label = Literal::string(&self.label);
+        tokens.append_all(quote! {
+            #result.map_err(|e| __argley::ArgError::in_field(e, #label))?
+        });
+    }
+}
+
 impl ToTokens for StructField {
+    /// Evaluates to whether the field added anything, reporting its boundaries to the consumer
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        let Self {
-            ref opts,
-            ref ident,
-            is_struct,
-            ..
-        } = *self;
+        let consumer = new_ident(ARG_CONSUMER);
+        let label = Literal::string(&self.label);
+        let name = match self.arg_name() {
+            Some(name) => quote! { ::std::option::Option::Some(#name) },
+            None => quote! { ::std::option::Option::None },
+        };
 
-        tokens.append_all(quote! { ::argley::Arg:: });
+        let mut add = TokenStream::new();
+        self.add_tokens(&mut add);
 
-        tokens.append_all({
-            let field_expr = {
-                let field_expr_base = if is_struct {
-                    quote! { &self.#ident }
-                } else {
-                    ident.to_token_stream()
-                };
+        tokens.append_all(quote! {
+            {
+                __argley::ArgConsumer::enter_field(#consumer, #label, #name);
+                let __argley_added = #add;
+                __argley::ArgConsumer::exit_field(#consumer);
+                __argley_added
+            }
+        });
+    }
+}
 
-                if let Some(fmt) = &opts.formatter {
-                    quote! { #fmt(#field_expr_base) }
-                } else {
-                    field_expr_base
-                }
+impl StructField {
+    /// Expression adding the field to the consumer & evaluating to whether it added anything
+    fn add_tokens(&self, tokens: &mut TokenStream) {
+        if self.fallible {
+            return self.to_tokens_fallible(tokens);
+        }
+
+        let opts = &self.opts;
+
+        if let Some(with) = &opts.with {
+            return tokens.append_all(self.with_call(with, &self.field_expr_base()));
+        }
+
+        let field_expr_base = self.field_expr_base();
+        let consumer = new_ident(ARG_CONSUMER);
+        let name = self.arg_name();
+
+        // Formatter output goes through method syntax so that references to types that only
+        // implement `Arg` themselves get dereferenced, while those of `&T: Arg` types don't
+        if let Some(formatted) = opts.formatter_call(&field_expr_base) {
+            let call = match name {
+                Some(name) => quote! { add_output_to(#name, #consumer) },
+                None => quote! { add_output_unnamed_to(#consumer) },
             };
-            let consumer = new_ident(ARG_CONSUMER);
+            return tokens.append_all(quote! {
+                {
+                    use __argley::AddFormatterOutput as _;
+                    (&#formatted).#call
+                }
+            });
+        }
 
-            if opts.position.is_some() || opts.variadic.is_some() {
-                quote! { add_unnamed_to(#field_expr, #consumer) }
+        tokens.append_all(quote! { __argley::Arg:: });
+
+        tokens.append_all({
+            let field_expr = if let Some(format_spec) = opts.format_spec() {
+                quote! { &__argley::Formatted::new(#field_expr_base, #format_spec) }
             } else {
-                let mut name = opts.name_prefix();
-                let span = if let Some(rename) = &opts.rename {
-                    let rename_str = rename.to_string();
-                    name.push_str(&rename_str[1..rename_str.len() - 1]);
-                    rename.span()
-                } else {
-                    name.push_str(&ident.to_string());
-                    Span::call_site()
-                };
-                let mut name = Literal::string(&name);
-                name.set_span(span);
+                field_expr_base
+            };
 
+            if let Some(name) = name {
                 quote! { add_to(#field_expr, #name, #consumer) }
+            } else {
+                quote! { add_unnamed_to(#field_expr, #consumer) }
             }
         });
     }
 }
 
+/// Error on fields sharing a position or an emitted name
+fn check_duplicates(fields: &[StructField]) -> syn::Result<()> {
+    let mut positions = HashMap::new();
+    let mut names = HashMap::new();
+
+    for field in fields {
+        if let Some(position) = field.opts.position {
+            if let Some(other) = positions.insert(position, &field.label) {
+                let span = field.opts.position_span.unwrap_or_else(Span::call_site);
+                return Err(syn::Error::new(
+                    span,
+                    format!("Position {position} is already used by field `{other}`"),
+                ));
+            }
+        }
+
+        if let Some(name) = field.arg_name() {
+            let name_str = name.to_string();
+            let name_str = name_str[1..name_str.len() - 1].to_owned();
+            if let Some(other) = names.get(&name_str) {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!("`{name_str}` is already emitted by field `{other}`"),
+                ));
+            }
+            names.insert(name_str, &field.label);
+        }
+    }
+
+    Ok(())
+}
+
 pub struct CollectFromIter {
     pub fields: Vec<StructField>,
     pub has_skips: bool,
@@ -166,6 +360,7 @@ struct FieldFilterMapper<'a, I> {
     is_struct: bool,
     has_skips: &'a mut bool,
     has_variadic: bool,
+    has_subcommand: bool,
     attr_collector: AttrCollector,
 }
 
@@ -176,6 +371,7 @@ impl<'a, I: Iterator<Item = Field>> FieldFilterMapper<'a, I> {
             src: src.into_iter().enumerate(),
             has_skips,
             has_variadic: false,
+            has_subcommand: false,
             attr_collector: Default::default(),
         }
     }
@@ -187,17 +383,42 @@ impl<'a, I: Iterator<Item = Field>> Iterator for FieldFilterMapper<'a, I> {
     fn next(&mut self) -> Option<Self::Item> {
         let (idx, field) = self.src.next()?;
 
+        let doc = doc_comment(&field.attrs);
         let mut opts = match self.attr_collector.next(field.attrs) {
             Ok(opts) => opts,
             Err(err) => return Some(Err(err)),
         };
 
+        if let Err(e) = opts
+            .check_formatting_conflicts()
+            .and_then(|()| opts.check_number_format_type(&field.ty))
+        {
+            return Some(Err(e));
+        }
+
         if opts.skip {
             *self.has_skips = true;
 
             return self.next();
         }
 
+        if let Some(ref subcommand) = opts.subcommand {
+            if let Some(ref global) = opts.global {
+                return Some(Err(syn::Error::new(
+                    global.span(),
+                    "A field can't be both `global` and a `subcommand`",
+                )));
+            }
+            if self.has_subcommand {
+                return Some(Err(syn::Error::new(
+                    subcommand.span(),
+                    "Only one subcommand field allowed",
+                )));
+            }
+
+            self.has_subcommand = true;
+        }
+
         if let Some(ref variadic) = opts.variadic {
             if self.has_variadic {
                 return Some(Err(syn::Error::new(
@@ -209,12 +430,18 @@ impl<'a, I: Iterator<Item = Field>> Iterator for FieldFilterMapper<'a, I> {
             self.has_variadic = true;
         }
 
+        let label = match field.ident {
+            Some(ref ident) => ident.to_string(),
+            None => idx.to_string(),
+        };
+
         let ident = if let Some(ident) = field.ident {
             FieldIdent::Ident(ident)
         } else {
             // Make unnamed by default
             if opts.is_default_field_name() {
                 opts.position = Some(idx.try_into().unwrap_or(u16::MAX));
+                opts.position_span = Some(field.ty.span());
             }
 
             let ident = FieldIdent::Idx(idx);
@@ -225,11 +452,19 @@ impl<'a, I: Iterator<Item = Field>> Iterator for FieldFilterMapper<'a, I> {
             }
         };
 
+        if let Err(e) = opts.check_unnamed_conflicts() {
+            return Some(Err(e));
+        }
+
         Some(Ok(StructField {
             opts,
             idx,
             is_struct: self.is_struct,
+            fallible: false,
+            label,
             ident,
+            ty: field.ty,
+            doc,
         }))
     }
 
@@ -0,0 +1,104 @@
+use std::iter::Sum;
+
+use proc_macro2::Ident;
+use syn::spanned::Spanned;
+use syn::{Attribute, Expr, ExprArray, LitStr};
+
+use crate::container_opts::parse_static_args;
+use crate::{parse_eq, unknown_option, OPT_SKIP};
+
+#[derive(Default)]
+pub struct VariantOpts {
+    pub value: Option<Expr>,
+    pub rename: Option<LitStr>,
+    pub static_args: Option<ExprArray>,
+    pub short: bool,
+    pub drop_name: bool,
+    pub skip: bool,
+}
+
+impl VariantOpts {
+    const OPTIONS: [&'static str; 6] = [
+        OPT_SKIP,
+        "value",
+        "rename",
+        "static_args",
+        "short",
+        "drop_name",
+    ];
+}
+
+impl TryFrom<Attribute> for VariantOpts {
+    type Error = syn::Error;
+
+    fn try_from(attr: Attribute) -> Result<Self, Self::Error> {
+        let mut opts = Self::default();
+
+        attr.parse_nested_meta(|meta| {
+            let ident: &Ident = match meta.path.get_ident() {
+                Some(ident) => ident,
+                None => {
+                    return Err(syn::Error::new(meta.path.span(), "Expected `Ident`"));
+                }
+            };
+
+            match ident.to_string().as_str() {
+                v if v == OPT_SKIP => {
+                    opts.skip = true;
+                }
+                "value" => {
+                    opts.value = Some(parse_eq(meta.input)?);
+                }
+                "rename" => {
+                    opts.rename = Some(parse_eq(meta.input)?);
+                }
+                "static_args" => {
+                    opts.static_args = Some(parse_static_args(meta.input)?);
+                }
+                "short" => {
+                    opts.short = true;
+                }
+                "drop_name" => {
+                    opts.drop_name = true;
+                }
+                _ => return Err(unknown_option(ident, &Self::OPTIONS)),
+            };
+
+            Ok(())
+        })?;
+
+        Ok(opts)
+    }
+}
+
+impl Sum for VariantOpts {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.reduce(move |mut acc, opts| {
+            if opts.value.is_some() {
+                acc.value = opts.value;
+            }
+            if opts.rename.is_some() {
+                acc.rename = opts.rename;
+            }
+            if let Some(add_args) = opts.static_args {
+                if let Some(ref mut static_args) = acc.static_args {
+                    static_args.elems.extend(add_args.elems);
+                } else {
+                    acc.static_args = Some(add_args);
+                }
+            }
+            if opts.short {
+                acc.short = true;
+            }
+            if opts.drop_name {
+                acc.drop_name = true;
+            }
+            if opts.skip {
+                acc.skip = true;
+            }
+
+            acc
+        })
+        .unwrap_or_default()
+    }
+}
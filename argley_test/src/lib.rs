@@ -9,7 +9,7 @@ mod test {
     use static_assertions::{assert_impl_one, assert_not_impl_all};
 
     use argley::prelude::*;
-    use argley::CollectedArgs;
+    use argley::{ArgErrorKind, CollectedArgs};
 
     type Str = &'static str;
 
@@ -90,6 +90,2130 @@ mod test {
         assert_eq!(result, source.collect_to::<CollectedArgs>(), "collect_to");
     }
 
+    #[test]
+    fn formatter_returning_derived_ref() {
+        #[derive(Arg)]
+        struct Inner {
+            a: u8,
+        }
+
+        struct Holder(Inner);
+
+        fn fmt_inner(h: &Holder) -> &Inner {
+            &h.0
+        }
+
+        #[derive(Arg)]
+        struct Outer {
+            #[arg(formatter = fmt_inner)]
+            h: Holder,
+        }
+
+        let result = Outer {
+            h: Holder(Inner { a: 1 }),
+        }
+        .collect_to::<CollectedArgs>();
+        assert_eq!(&result[..], &["--h", "--a", "1"]);
+    }
+
+    #[test]
+    fn own_reference_impl() {
+        struct Theirs;
+
+        impl Arg for Theirs {
+            fn add_unnamed_to(&self, consumer: &mut impl ArgConsumer) -> bool {
+                consumer.add_arg("owned");
+                true
+            }
+        }
+
+        impl Arg for &Theirs {
+            fn add_unnamed_to(&self, consumer: &mut impl ArgConsumer) -> bool {
+                consumer.add_arg("borrowed");
+                true
+            }
+        }
+
+        fn fmt_theirs(_: &u8) -> &'static Theirs {
+            &Theirs
+        }
+
+        #[derive(Arg)]
+        struct Args {
+            #[arg(formatter = fmt_theirs)]
+            a: u8,
+        }
+
+        assert_eq!(
+            &<&Theirs>::collect_to::<CollectedArgs>(&&Theirs)[..],
+            &["borrowed"]
+        );
+        assert_eq!(
+            &Args { a: 0 }.collect_to::<CollectedArgs>()[..],
+            &["--a", "borrowed"]
+        );
+    }
+
+    #[test]
+    fn formatter_closure() {
+        #[derive(Arg)]
+        struct WithClosure {
+            #[arg(formatter = |v| v * 2)]
+            doubled: u8,
+
+            #[arg(formatter = |v: &Vec<u8>| v.len())]
+            #[arg(rename = "count")]
+            values: Vec<u8>,
+
+            #[arg(formatter = |v| format!("{v}!"), position = 0)]
+            owned: Str,
+        }
+
+        let source = WithClosure {
+            doubled: 4,
+            values: vec![1, 2, 3],
+            owned: "hi",
+        };
+
+        let result = source.collect_to::<CollectedArgs>();
+        assert_eq!(&result[..], &["--doubled", "8", "--count", "3", "hi!"]);
+    }
+
+    #[test]
+    fn maps() {
+        use std::collections::{BTreeMap, HashMap};
+
+        #[derive(Arg)]
+        struct Maps {
+            #[arg(short, rename = "D")]
+            defines: HashMap<Str, u8>,
+
+            #[arg(kv_separator = ":", kv_join = ",")]
+            labels: BTreeMap<Str, Str>,
+
+            #[arg(kv_repeat_name = false)]
+            set: Option<BTreeMap<Str, Option<u8>>>,
+
+            empty: HashMap<Str, Str>,
+        }
+
+        let source = Maps {
+            defines: HashMap::from([("b", 2), ("c", 3), ("a", 1)]),
+            labels: BTreeMap::from([("env", "prod"), ("app", "web")]),
+            set: Some(BTreeMap::from([("x", Some(1)), ("flag", None)])),
+            empty: HashMap::new(),
+        };
+
+        let result = source.collect_to::<CollectedArgs>();
+        assert_eq!(
+            &result[..],
+            &[
+                "-D",
+                "a=1",
+                "-D",
+                "b=2",
+                "-D",
+                "c=3",
+                "--labels",
+                "app:web,env:prod",
+                "--set",
+                "flag",
+                "x=1"
+            ]
+        );
+    }
+
+    mod generic_bounds {
+        use std::marker::PhantomData;
+
+        use super::Str;
+
+        use argley::prelude::*;
+        use argley::CollectedArgs;
+
+        #[test]
+        fn inferred() {
+            #[derive(Arg)]
+            struct Wrapper<T, U> {
+                value: T,
+                values: Vec<T>,
+                #[arg(skip)]
+                ignored: U,
+                marker: PhantomData<U>,
+            }
+
+            struct NotArg;
+
+            let source = Wrapper {
+                value: 1u8,
+                values: vec![2, 3],
+                ignored: NotArg,
+                marker: PhantomData::<NotArg>,
+            };
+
+            let result = source.collect_to::<CollectedArgs>();
+            assert_eq!(&result[..], &["--value", "1", "--values", "2", "3"]);
+        }
+
+        #[test]
+        fn inferred_fallible() {
+            #[derive(Arg)]
+            #[arg(fallible)]
+            enum Either<L, R> {
+                Left(L),
+                Right { value: R },
+            }
+
+            let result = Either::<u8, Str>::Left(1).try_collect_to::<CollectedArgs>();
+            assert_eq!(&result.unwrap()[..], &["1"]);
+
+            let result = Either::<u8, Str>::Right { value: "x" }.try_collect_to::<CollectedArgs>();
+            assert_eq!(&result.unwrap()[..], &["--value", "x"]);
+        }
+
+        #[test]
+        fn inferred_format_spec() {
+            use std::collections::BTreeMap;
+
+            #[derive(Arg)]
+            struct Formatted<F, K> {
+                #[arg(precision = 1)]
+                ratio: F,
+
+                #[arg(kv_join = ",")]
+                map: BTreeMap<K, u8>,
+            }
+
+            let mut map = BTreeMap::new();
+            map.insert("a", 1);
+
+            let result = Formatted {
+                ratio: 0.25f64,
+                map,
+            }
+            .collect_to::<CollectedArgs>();
+            assert_eq!(&result[..], &["--ratio", "0.2", "--map", "a=1"]);
+
+            #[derive(Arg)]
+            #[arg(fallible)]
+            struct Paths<P> {
+                #[arg(path = "expand_home")]
+                path: Option<P>,
+            }
+
+            let result = Paths {
+                path: Some(std::path::PathBuf::from("/tmp")),
+            }
+            .try_collect_to::<CollectedArgs>()
+            .unwrap();
+            assert_eq!(&result[..], &["--path", "/tmp"]);
+        }
+
+        #[test]
+        fn explicit() {
+            trait Label {
+                const LABEL: &'static str;
+            }
+
+            struct Zip;
+            impl Label for Zip {
+                const LABEL: &'static str = "zip";
+            }
+
+            #[derive(Arg)]
+            #[arg(bound = "L: Label")]
+            struct Labelled<L> {
+                #[arg(formatter = |_| L::LABEL)]
+                format: PhantomData<L>,
+            }
+
+            let source = Labelled::<Zip> {
+                format: PhantomData,
+            };
+            let result = source.collect_to::<CollectedArgs>();
+            assert_eq!(&result[..], &["--format", "zip"]);
+        }
+    }
+
+    #[test]
+    fn arg_names() {
+        use argley::ArgSchema;
+
+        #[derive(Arg)]
+        #[arg(flag_enum)]
+        enum Mode {
+            FastMode,
+            Level {
+                #[arg(short)]
+                l: u8,
+            },
+        }
+
+        #[derive(Arg)]
+        #[arg(subcommand)]
+        enum Cmd {
+            Run,
+        }
+
+        #[derive(Arg)]
+        #[arg(drop_name)]
+        struct Unnamed(u8);
+
+        #[derive(Arg)]
+        #[arg(fallible, drop_name)]
+        struct TryUnnamed(u8);
+
+        #[derive(Arg)]
+        #[arg(fallible, schema)]
+        struct Names {
+            #[arg(position = 0)]
+            input: Str,
+            #[arg(rename = "out-dir")]
+            output_dir: Str,
+            #[arg(skip)]
+            skipped: bool,
+            #[arg(short)]
+            v: bool,
+            mode: Option<Mode>,
+            cmd: Cmd,
+            unnamed: Unnamed,
+            try_unnamed: TryUnnamed,
+        }
+
+        assert_eq!(Names::names(), [("output_dir", "--out-dir"), ("v", "-v")]);
+
+        #[derive(Arg)]
+        #[arg(flag_enum, schema)]
+        enum SchemaMode {
+            FastMode,
+            Level {
+                #[arg(short)]
+                l: u8,
+                unnamed: Unnamed,
+            },
+        }
+
+        assert_eq!(
+            SchemaMode::names(),
+            [
+                ("FastMode", "--fast-mode"),
+                ("Level", "--level"),
+                ("Level.l", "-l")
+            ]
+        );
+    }
+
+    mod schema {
+        use std::collections::HashMap;
+
+        use argley::prelude::*;
+        use argley::{ArgSchema, FieldKind, FieldSchema, SchemaKind};
+        use static_assertions::assert_not_impl_all;
+
+        type Parts = (
+            &'static str,
+            Option<&'static str>,
+            FieldKind,
+            Option<u16>,
+            &'static str,
+            bool,
+            Option<&'static str>,
+            Option<&'static str>,
+        );
+
+        fn parts(field: &FieldSchema) -> Parts {
+            (
+                field.label,
+                field.name,
+                field.kind,
+                field.position,
+                field.type_name,
+                field.optional,
+                field.value_name,
+                field.doc,
+            )
+        }
+
+        /// Build something
+        #[derive(Arg)]
+        #[arg(subcommand, schema)]
+        enum Cmd<T> {
+            /// Build the project
+            ///
+            /// In release mode
+            Build {
+                release: bool,
+                #[arg(position = 0)]
+                target: T,
+            },
+            Clean,
+            #[arg(skip)]
+            Hidden {
+                x: u8,
+            },
+        }
+
+        #[test]
+        fn struct_schema() {
+            #[derive(Arg)]
+            #[arg(schema)]
+            struct Args<'a> {
+                /// Be loud
+                #[arg(short)]
+                v: bool,
+                #[arg(skip)]
+                skipped: u8,
+                #[arg(rename = "env")]
+                envs: HashMap<String, Option<&'a str>>,
+                #[arg(variadic)]
+                rest: Vec<String>,
+                #[arg(subcommand)]
+                cmd: Cmd<u8>,
+            }
+
+            let schema = Args::schema();
+            assert_eq!(schema.name, "Args");
+            assert_eq!(schema.doc, None);
+
+            let expected = [
+                (
+                    "v",
+                    Some("-v"),
+                    FieldKind::Flag,
+                    None,
+                    "bool",
+                    true,
+                    None,
+                    Some("Be loud"),
+                ),
+                (
+                    "envs",
+                    Some("--env"),
+                    FieldKind::Option,
+                    None,
+                    "HashMap<String, Option<&'a str>>",
+                    true,
+                    None,
+                    None,
+                ),
+                (
+                    "rest",
+                    None,
+                    FieldKind::Variadic,
+                    None,
+                    "Vec<String>",
+                    true,
+                    None,
+                    None,
+                ),
+                (
+                    "cmd",
+                    None,
+                    FieldKind::Nested,
+                    None,
+                    "Cmd<u8>",
+                    false,
+                    None,
+                    None,
+                ),
+            ];
+            match schema.kind {
+                SchemaKind::Struct(fields) => {
+                    assert_eq!(fields.iter().map(parts).collect::<Vec<_>>(), expected)
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        #[test]
+        fn enum_schema() {
+            let schema = Cmd::<String>::schema();
+            assert_eq!(schema.name, "Cmd");
+            assert_eq!(schema.doc, Some("Build something"));
+
+            let variants = match schema.kind {
+                SchemaKind::Enum(variants) => variants,
+                _ => unreachable!(),
+            };
+            assert_eq!(variants.len(), 2);
+
+            assert_eq!(variants[0].label, "Build");
+            assert_eq!(variants[0].name, Some("build"));
+            assert_eq!(
+                variants[0].doc,
+                Some("Build the project\n\nIn release mode")
+            );
+            assert_eq!(
+                variants[0].fields.iter().map(parts).collect::<Vec<_>>(),
+                [
+                    (
+                        "release",
+                        Some("--release"),
+                        FieldKind::Flag,
+                        None,
+                        "bool",
+                        true,
+                        None,
+                        None
+                    ),
+                    (
+                        "target",
+                        None,
+                        FieldKind::Positional,
+                        Some(0),
+                        "T",
+                        false,
+                        None,
+                        None
+                    ),
+                ]
+            );
+
+            assert_eq!(variants[1].label, "Clean");
+            assert_eq!(variants[1].name, Some("clean"));
+            assert_eq!(variants[1].fields, &[]);
+            assert_eq!(variants[1].doc, None);
+        }
+
+        #[test]
+        fn usage() {
+            assert_eq!(
+                Cmd::<u8>::schema().usage("cargo"),
+                "cargo build [--release] <TARGET>\ncargo clean"
+            );
+
+            type Threads = Option<u8>;
+
+            struct Level(u8);
+
+            impl Arg for Level {
+                const OPTIONAL: bool = true;
+
+                fn add_unnamed_to(&self, consumer: &mut impl ArgConsumer) -> bool {
+                    self.0 != 0 && self.0.add_unnamed_to(consumer)
+                }
+            }
+
+            #[derive(Arg)]
+            #[arg(fallible, schema)]
+            struct Args {
+                #[arg(position = 1)]
+                second: u8,
+                #[arg(position = 0, value_name = "FIRST")]
+                a: Option<u8>,
+                #[arg(subcommand, value_name = "COMMAND")]
+                cmd: Cmd<u8>,
+                #[arg(short)]
+                v: bool,
+                jobs: std::option::Option<u8>,
+                threads: Threads,
+                level: Level,
+                features: Vec<String>,
+                #[arg(required)]
+                target: Vec<String>,
+            }
+
+            assert_eq!(
+                Args::schema().usage("prog"),
+                "prog [-v] [--jobs <JOBS>] [--threads <THREADS>] [--level <LEVEL>] [--features <FEATURES>] --target <TARGET> [<FIRST>] <SECOND> <COMMAND>"
+            );
+        }
+
+        #[test]
+        fn markdown() {
+            assert_eq!(
+                Cmd::<u8>::schema().markdown(),
+                "\
+**`build`**: Build the project<br>In release mode
+
+| Option | Type | Description |
+|---|---|---|
+| `--release` | `bool` |  |
+| `<TARGET>` | `T` |  |
+"
+            );
+        }
+
+        #[test]
+        fn value_schema() {
+            #[derive(Arg)]
+            #[arg(as_repr, schema)]
+            #[repr(u8)]
+            enum Level {
+                Low,
+            }
+
+            assert_eq!(Level::schema().kind, SchemaKind::Value);
+        }
+
+        #[test]
+        fn opt_in() {
+            #[derive(Arg)]
+            struct Args {
+                verbose: bool,
+            }
+
+            assert_not_impl_all!(Args: ArgSchema);
+        }
+    }
+
+    mod builder {
+        use argley::prelude::*;
+        use argley::CollectedArgs;
+
+        #[derive(Arg, ArgBuilder, Default, Debug, PartialEq)]
+        #[non_exhaustive]
+        pub struct Args {
+            pub verbose: bool,
+            pub level: Option<u8>,
+            pub tags: Vec<String>,
+            #[arg(position = 0)]
+            pub input: String,
+        }
+
+        #[test]
+        fn build() {
+            let args = Args::builder()
+                .level(3)
+                .input("file.txt")
+                .tags(vec!["a".to_owned()])
+                .build();
+
+            let expected = Args {
+                level: Some(3),
+                input: "file.txt".into(),
+                tags: vec!["a".into()],
+                ..Default::default()
+            };
+
+            assert_eq!(args, expected);
+        }
+
+        #[test]
+        fn add_arg_set() {
+            let mut args = CollectedArgs::new();
+            Args::builder()
+                .verbose(true)
+                .level(3)
+                .input("file.txt")
+                .add_arg_set(&mut args);
+
+            assert_eq!(&args[..], &["--verbose", "--level", "3", "file.txt"]);
+        }
+
+        #[test]
+        fn without_arg() {
+            #[derive(ArgBuilder, Default, Debug, PartialEq)]
+            struct Plain {
+                a: Option<String>,
+                b: bool,
+            }
+
+            let plain = Plain::builder().a("x").b(true).build();
+            assert_eq!(
+                plain,
+                Plain {
+                    a: Some("x".into()),
+                    b: true
+                }
+            );
+        }
+
+        #[test]
+        fn fallible() {
+            #[derive(Arg, ArgBuilder, Default)]
+            #[arg(fallible)]
+            struct Checked<T> {
+                #[arg(non_empty)]
+                name: String,
+                value: Option<T>,
+            }
+
+            let mut args = CollectedArgs::new();
+            let result = Checked::<u8>::builder().value(1).try_add_arg_set(&mut args);
+            assert!(result.is_err());
+
+            let result = Checked::<u8>::builder()
+                .name("x")
+                .value(1)
+                .try_add_arg_set(&mut args)
+                .map(move |args| args.to_vec());
+            assert_eq!(result.unwrap(), &["--name", "x", "--value", "1"]);
+        }
+
+        #[test]
+        fn clear() {
+            let base = Args::builder().level(3).input("file.txt");
+            let args = base.clear_level().build();
+
+            let expected = Args {
+                input: "file.txt".into(),
+                ..Default::default()
+            };
+
+            assert_eq!(args, expected);
+        }
+    }
+
+    mod from_args {
+        use std::path::PathBuf;
+
+        use argley::prelude::*;
+        use argley::{CollectedArgs, FromArgsError};
+
+        #[derive(Arg, FromArgs, Debug, PartialEq, Default)]
+        #[arg(static_args = ["run"])]
+        struct Args {
+            #[arg(short)]
+            v: bool,
+            quiet: bool,
+            #[arg(rename = "out")]
+            output: PathBuf,
+            level: Option<u8>,
+            #[arg(skip)]
+            skipped: u8,
+            #[arg(position = 1)]
+            second: String,
+            #[arg(position = 0)]
+            first: i32,
+            #[arg(variadic)]
+            rest: Vec<String>,
+        }
+
+        fn round_trip<T: Arg + FromArgs + PartialEq + std::fmt::Debug>(value: T) {
+            let args = value.collect_to::<CollectedArgs>();
+            assert_eq!(T::from_args(&args).unwrap(), value, "{args:?}");
+        }
+
+        #[test]
+        fn round_trips() {
+            round_trip(Args {
+                v: true,
+                output: "/tmp/out".into(),
+                level: Some(3),
+                second: "2nd".into(),
+                first: -1,
+                rest: vec!["x".into(), "y".into()],
+                ..Default::default()
+            });
+            round_trip(Args {
+                quiet: true,
+                second: "2nd".into(),
+                ..Default::default()
+            });
+
+            #[derive(Arg, FromArgs, Debug, PartialEq)]
+            struct Tuple(u8, #[arg(rename = "name")] String);
+            round_trip(Tuple(1, "x".into()));
+
+            #[derive(Arg, FromArgs, Debug, PartialEq)]
+            struct Generic<T> {
+                value: T,
+                values: Vec<T>,
+                #[arg(short)]
+                o: Option<Vec<T>>,
+            }
+            round_trip(Generic {
+                value: 7u64,
+                values: vec![1, 2],
+                o: Some(vec![3]),
+            });
+        }
+
+        #[test]
+        fn multi_value_before_positional() {
+            #[derive(Arg, FromArgs, Debug, PartialEq)]
+            struct Files {
+                tags: Vec<String>,
+                #[arg(position = 0)]
+                input: String,
+            }
+
+            // Multi-value fields leave enough arguments for the required positional fields
+            round_trip(Files {
+                tags: vec!["a".into(), "b".into()],
+                input: "in".into(),
+            });
+        }
+
+        #[test]
+        fn collections() {
+            use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+            #[derive(Arg, FromArgs, Debug, PartialEq, Default)]
+            struct Collections {
+                set: BTreeSet<u8>,
+                hash_set: HashSet<String>,
+                deque: VecDeque<char>,
+                env: BTreeMap<String, u8>,
+                labels: HashMap<String, Option<String>>,
+                lists: BTreeMap<String, Vec<u8>>,
+                #[arg(position = 0)]
+                sep: char,
+            }
+
+            round_trip(Collections {
+                set: [3, 1].into_iter().collect(),
+                hash_set: ["a".to_owned()].into_iter().collect(),
+                deque: ['x', 'y'].into_iter().collect(),
+                env: [("A".to_owned(), 1), ("B".to_owned(), 2)]
+                    .into_iter()
+                    .collect(),
+                labels: [("k".to_owned(), None), ("v".to_owned(), Some("1=2".into()))]
+                    .into_iter()
+                    .collect(),
+                lists: [("l".to_owned(), vec![1, 2])].into_iter().collect(),
+                sep: ',',
+            });
+            round_trip(Collections::default());
+        }
+
+        #[test]
+        fn nested() {
+            #[derive(Arg, FromArgs, Debug, PartialEq, Default)]
+            #[arg(drop_name)]
+            struct Db {
+                host: String,
+                port: Option<u16>,
+            }
+
+            #[derive(Arg, FromArgs, Debug, PartialEq, Default)]
+            #[arg(static_args = ["--"])]
+            struct Log {
+                level: Option<u8>,
+            }
+
+            #[derive(Arg, FromArgs, Debug, PartialEq, Clone, Copy)]
+            #[arg(value_enum)]
+            enum Format {
+                Json,
+                #[arg(rename = "yml")]
+                Yaml,
+            }
+
+            #[derive(Arg, FromArgs, Debug, PartialEq)]
+            struct Serve {
+                db: Db,
+                log: Log,
+                format: Format,
+                output: Option<Format>,
+                #[arg(position = 0)]
+                input: String,
+            }
+
+            round_trip(Serve {
+                db: Db {
+                    host: "h".into(),
+                    port: Some(1),
+                },
+                log: Log { level: Some(2) },
+                format: Format::Yaml,
+                output: Some(Format::Json),
+                input: "in".into(),
+            });
+            round_trip(Serve {
+                db: Db::default(),
+                log: Log::default(),
+                format: Format::Json,
+                output: None,
+                input: "--format".into(),
+            });
+
+            round_trip(Format::Yaml);
+            assert_eq!(
+                Format::from_args(&["xml"]),
+                Err(FromArgsError::InvalidValue {
+                    field: "Format",
+                    value: "xml".into(),
+                    reason: "expected one of `json`, `yml`".into(),
+                })
+            );
+        }
+
+        #[test]
+        fn values_equal_to_names() {
+            #[derive(Arg, FromArgs, Debug, PartialEq)]
+            struct Named {
+                verbose: bool,
+                name: String,
+                level: Option<String>,
+            }
+
+            round_trip(Named {
+                verbose: false,
+                name: "--verbose".into(),
+                level: Some("--name".into()),
+            });
+            round_trip(Named {
+                verbose: true,
+                name: "--level".into(),
+                level: None,
+            });
+        }
+
+        #[test]
+        fn errors() {
+            assert_eq!(
+                Args::from_args(&["--out", "x"]),
+                Err(FromArgsError::MissingStaticArg { expected: "run" })
+            );
+            assert_eq!(
+                Args::from_args(&["run", "--out", "x", "1"]),
+                Err(FromArgsError::MissingField { field: "second" })
+            );
+            assert_eq!(
+                Args::from_args(&["run", "1", "2nd"]),
+                Err(FromArgsError::MissingField { field: "output" })
+            );
+            assert_eq!(
+                Args::from_args(&["run", "--out"]),
+                Err(FromArgsError::MissingValue { field: "output" })
+            );
+            assert_eq!(
+                Args::from_args(&["run", "--quiet", "-v", "--out", "x", "1", "2nd"]),
+                Err(FromArgsError::MissingField { field: "output" })
+            );
+            assert!(matches!(
+                Args::from_args(&["run", "--out", "x", "--level", "high", "1", "2nd"]),
+                Err(FromArgsError::InvalidValue { field: "level", .. })
+            ));
+
+            #[derive(Arg, FromArgs, Debug)]
+            struct Single {
+                value: u8,
+            }
+            assert_eq!(
+                Single::from_args(&["--value", "1", "extra"]).unwrap_err(),
+                FromArgsError::Unexpected {
+                    arg: "extra".into()
+                }
+            );
+        }
+    }
+
+    mod merge {
+        use std::collections::BTreeMap;
+        use std::marker::PhantomData;
+
+        use argley::prelude::*;
+        use argley::CollectedArgs;
+
+        #[derive(Arg, Merge, Default, Debug, PartialEq)]
+        #[arg(drop_name)]
+        struct Db {
+            host: Option<String>,
+            port: Option<u16>,
+        }
+
+        #[derive(Arg, Merge, Debug, PartialEq)]
+        #[arg(subcommand)]
+        enum Cmd {
+            Build {
+                release: bool,
+                #[arg(merge = "replace")]
+                targets: Vec<String>,
+            },
+            Run(Option<String>, #[arg(skip)] u8),
+            Clean,
+        }
+
+        #[derive(Arg, Merge, Debug, PartialEq)]
+        struct Args {
+            verbose: bool,
+            jobs: Option<u8>,
+            features: Vec<String>,
+            #[arg(merge = "replace")]
+            exclude: Vec<String>,
+            env: BTreeMap<String, String>,
+            db: Db,
+            #[arg(skip, merge = "skip")]
+            layer: u8,
+            #[arg(skip, merge = "deep")]
+            cache: Option<Db>,
+            #[arg(subcommand)]
+            cmd: Cmd,
+        }
+
+        impl Args {
+            fn new(layer: u8, cmd: Cmd) -> Self {
+                Self {
+                    verbose: false,
+                    jobs: None,
+                    features: Vec::new(),
+                    exclude: Vec::new(),
+                    env: BTreeMap::new(),
+                    db: Db::default(),
+                    layer,
+                    cache: None,
+                    cmd,
+                }
+            }
+        }
+
+        fn build(release: bool, targets: &[&str]) -> Cmd {
+            Cmd::Build {
+                release,
+                targets: targets.iter().map(move |t| t.to_string()).collect(),
+            }
+        }
+
+        #[test]
+        fn layers() {
+            let mut args = Args {
+                jobs: Some(4),
+                features: vec!["a".into()],
+                exclude: vec!["x".into()],
+                env: [("A".into(), "1".into())].into_iter().collect(),
+                db: Db {
+                    host: Some("localhost".into()),
+                    port: Some(5432),
+                },
+                ..Args::new(0, build(false, &["x86"]))
+            };
+
+            args.merge(Args {
+                verbose: true,
+                features: vec!["b".into()],
+                env: [("A".into(), "2".into()), ("B".into(), "3".into())]
+                    .into_iter()
+                    .collect(),
+                db: Db {
+                    host: Some("db".into()),
+                    port: None,
+                },
+                cache: Some(Db {
+                    host: Some("cache".into()),
+                    port: None,
+                }),
+                ..Args::new(1, build(true, &[]))
+            });
+            args.merge(Args {
+                exclude: vec!["y".into()],
+                cache: Some(Db {
+                    host: None,
+                    port: Some(6379),
+                }),
+                ..Args::new(2, build(false, &["arm"]))
+            });
+
+            let result = args.collect_to::<CollectedArgs>();
+            assert_eq!(
+                &result[..],
+                &[
+                    "build",
+                    "--release",
+                    "--targets",
+                    "arm",
+                    "--verbose",
+                    "--jobs",
+                    "4",
+                    "--features",
+                    "a",
+                    "b",
+                    "--exclude",
+                    "y",
+                    "--env",
+                    "A=2",
+                    "--env",
+                    "B=3",
+                    "--host",
+                    "db",
+                    "--port",
+                    "5432",
+                ]
+            );
+            assert_eq!(args.layer, 0);
+            assert_eq!(
+                args.cache,
+                Some(Db {
+                    host: Some("cache".into()),
+                    port: Some(6379),
+                })
+            );
+        }
+
+        #[test]
+        fn enum_variants() {
+            let mut cmd = Cmd::Run(Some("app".into()), 1);
+            cmd.merge(Cmd::Run(None, 2));
+            assert_eq!(cmd, Cmd::Run(Some("app".into()), 2));
+
+            cmd.merge(Cmd::Clean);
+            assert_eq!(cmd, Cmd::Clean);
+
+            cmd.merge(build(false, &["x86"]));
+            assert_eq!(cmd, build(false, &["x86"]));
+        }
+
+        #[test]
+        fn generic() {
+            #[derive(Merge, Debug, PartialEq)]
+            struct Generic<T, U>(T, Vec<U>, PhantomData<U>);
+
+            let mut value = Generic(Some(1), vec!['a'], PhantomData);
+            value.merge(Generic(None, vec!['b'], PhantomData));
+            assert_eq!(value, Generic(Some(1), vec!['a', 'b'], PhantomData));
+
+            #[derive(Merge, Debug, PartialEq)]
+            struct Deep<T>(#[arg(merge = "deep")] Option<T>);
+
+            let mut value = Deep(Some(vec![1]));
+            value.merge(Deep(Some(vec![2])));
+            assert_eq!(value, Deep(Some(vec![1, 2])));
+        }
+    }
+
+    mod diff {
+        use std::collections::BTreeMap;
+        use std::path::PathBuf;
+
+        use argley::prelude::*;
+        use argley::{ArgChange, ArgDiff, CollectedArgs};
+
+        #[derive(Arg, Clone)]
+        #[arg(drop_name)]
+        struct Db {
+            host: String,
+            port: Option<u16>,
+        }
+
+        #[derive(Arg, Clone)]
+        #[arg(subcommand)]
+        enum Cmd {
+            Build { release: bool },
+            Test,
+        }
+
+        #[derive(Arg, Clone)]
+        #[arg(static_args = ["run"])]
+        struct Args {
+            jobs: Option<u8>,
+            env: BTreeMap<&'static str, &'static str>,
+            db: Db,
+            #[arg(subcommand)]
+            cmd: Cmd,
+            #[arg(position = 0)]
+            input: String,
+        }
+
+        fn args() -> Args {
+            Args {
+                jobs: Some(4),
+                env: [("A", "1")].into_iter().collect(),
+                db: Db {
+                    host: "localhost".into(),
+                    port: None,
+                },
+                cmd: Cmd::Build { release: true },
+                input: "a.txt".into(),
+            }
+        }
+
+        #[test]
+        fn fields() {
+            let old = args();
+            assert!(ArgDiff::between(&old, &old).is_empty());
+
+            let new = Args {
+                jobs: None,
+                env: [("A", "1"), ("B", "2")].into_iter().collect(),
+                db: Db {
+                    port: Some(5432),
+                    ..old.db.clone()
+                },
+                cmd: Cmd::Test,
+                input: "b.txt".into(),
+            };
+            let diff = ArgDiff::between(&old, &new);
+
+            assert_eq!(
+                diff.changes(),
+                &[
+                    ArgChange::Changed {
+                        option: "cmd".into(),
+                        old: vec!["build".into()],
+                        new: vec!["test".into()],
+                    },
+                    ArgChange::Removed {
+                        option: "--release".into(),
+                        value: Vec::new(),
+                    },
+                    ArgChange::Removed {
+                        option: "--jobs".into(),
+                        value: vec!["4".into()],
+                    },
+                    ArgChange::Changed {
+                        option: "--env".into(),
+                        old: vec!["A=1".into()],
+                        new: vec!["A=1".into(), "B=2".into()],
+                    },
+                    ArgChange::Changed {
+                        option: "input".into(),
+                        old: vec!["a.txt".into()],
+                        new: vec!["b.txt".into()],
+                    },
+                    ArgChange::Added {
+                        option: "--port".into(),
+                        value: vec!["5432".into()],
+                    },
+                ][..]
+            );
+            assert_eq!(
+                diff.to_string(),
+                "~ cmd: build -> test\n\
+                 - --release\n\
+                 - --jobs 4\n\
+                 ~ --env: A=1 -> A=1 B=2\n\
+                 ~ input: a.txt -> b.txt\n\
+                 + --port 5432\n"
+            );
+        }
+
+        #[test]
+        fn values_equal_to_names() {
+            #[derive(Arg)]
+            struct Tags {
+                tag: String,
+                tags: Vec<String>,
+            }
+
+            let diff = ArgDiff::between(
+                &Tags {
+                    tag: "--tag".into(),
+                    tags: vec!["a".into(), "--tags".into()],
+                },
+                &Tags {
+                    tag: "--tag".into(),
+                    tags: vec!["--tags".into()],
+                },
+            );
+            assert_eq!(diff.to_string(), "~ --tags: a --tags -> --tags\n");
+
+            let diff = ArgDiff::between(
+                &Tags {
+                    tag: "x".into(),
+                    tags: vec!["a".into(), "--tags".into(), "b".into()],
+                },
+                &Tags {
+                    tag: "x".into(),
+                    tags: vec!["a".into(), "--tags".into(), "c".into()],
+                },
+            );
+            assert_eq!(diff.to_string(), "~ --tags: a --tags b -> a --tags c\n");
+        }
+
+        #[test]
+        fn fallible() {
+            #[derive(Arg)]
+            #[arg(fallible)]
+            struct Paths {
+                #[arg(path = "absolute")]
+                out: PathBuf,
+            }
+
+            let diff = ArgDiff::try_between(
+                &Paths {
+                    out: "/tmp/a".into(),
+                },
+                &Paths {
+                    out: "/tmp/b".into(),
+                },
+            )
+            .unwrap();
+            assert_eq!(diff.to_string(), "~ --out: /tmp/a -> /tmp/b\n");
+        }
+
+        #[test]
+        fn collected() {
+            let old = args().collect_to::<CollectedArgs>();
+            let new = ["run", "test", "--jobs", "-1", "--env", "A=1", "--verbose"];
+
+            // Without the fields, the trailing positional reads as one of `--host`'s values
+
+            assert_eq!(
+                ArgDiff::between_collected(&old, &new.map(Into::into)).to_string(),
+                "~ #1: build -> test\n\
+                 - --release\n\
+                 ~ --jobs: 4 -> -1\n\
+                 - --host localhost a.txt\n\
+                 + --verbose\n"
+            );
+        }
+    }
+
+    mod trace {
+        use argley::prelude::*;
+        use argley::{ArgTracer, TracedArgKind};
+
+        #[derive(Arg)]
+        #[arg(value_enum)]
+        enum Format {
+            Json,
+        }
+
+        #[derive(Arg)]
+        #[arg(flag_enum)]
+        enum Compression {
+            Gzip,
+        }
+
+        #[derive(Arg)]
+        #[arg(subcommand)]
+        enum Cmd {
+            #[arg(static_args = ["--color"])]
+            Build {
+                #[arg(formatter = |v: &u8| v * 2)]
+                jobs: u8,
+            },
+        }
+
+        #[derive(Arg)]
+        #[arg(schema, static_args = ["run"])]
+        struct Outer {
+            format: Format,
+            compression: Compression,
+            #[arg(subcommand)]
+            cmd: Cmd,
+        }
+
+        fn traced(tracer: &ArgTracer) -> Vec<(&str, &str, TracedArgKind)> {
+            tracer
+                .args()
+                .iter()
+                .map(move |a| (a.arg.to_str().unwrap(), a.path.as_str(), a.kind))
+                .collect()
+        }
+
+        #[test]
+        fn nested() {
+            let tracer = ArgTracer::of(&Outer {
+                format: Format::Json,
+                compression: Compression::Gzip,
+                cmd: Cmd::Build { jobs: 2 },
+            });
+
+            assert_eq!(
+                traced(&tracer),
+                [
+                    ("run", "Outer", TracedArgKind::Static),
+                    ("build", "Outer.cmd::Build", TracedArgKind::Name),
+                    ("--color", "Outer.cmd::Build", TracedArgKind::Static),
+                    ("--jobs", "Outer.cmd::Build.jobs", TracedArgKind::Name),
+                    ("4", "Outer.cmd::Build.jobs", TracedArgKind::Value),
+                    ("--format", "Outer.format", TracedArgKind::Name),
+                    ("json", "Outer.format::Json", TracedArgKind::Value),
+                    ("--gzip", "Outer.compression::Gzip", TracedArgKind::Name),
+                ]
+            );
+            assert_eq!(
+                tracer.to_string().lines().nth(4),
+                Some(r#""4" <- Outer.cmd::Build.jobs (value)"#)
+            );
+        }
+
+        #[test]
+        fn enum_root() {
+            #[derive(Arg)]
+            #[arg(schema)]
+            enum Mode {
+                Named { c: u8 },
+                Tuple(#[arg(skip)] u8, String),
+            }
+
+            assert_eq!(
+                traced(&ArgTracer::of(&Mode::Named { c: 1 })),
+                [
+                    ("--c", "Mode::Named.c", TracedArgKind::Name),
+                    ("1", "Mode::Named.c", TracedArgKind::Value),
+                ]
+            );
+            assert_eq!(
+                traced(&ArgTracer::of(&Mode::Tuple(0, "x".into()))),
+                [("x", "Mode::Tuple.1", TracedArgKind::Value)]
+            );
+        }
+
+        #[test]
+        fn fallible() {
+            #[derive(Arg)]
+            #[arg(fallible, schema)]
+            struct Limits {
+                #[arg(range = 1..=10)]
+                level: u8,
+            }
+
+            let tracer = ArgTracer::try_of(&Limits { level: 3 }).unwrap();
+            assert_eq!(
+                traced(&tracer),
+                [
+                    ("--level", "Limits.level", TracedArgKind::Name),
+                    ("3", "Limits.level", TracedArgKind::Value),
+                ]
+            );
+            assert!(ArgTracer::try_of(&Limits { level: 11 }).is_err());
+        }
+
+        #[test]
+        fn try_add_arg_set() {
+            #[derive(Arg)]
+            #[arg(drop_name)]
+            struct Db {
+                host: String,
+            }
+
+            #[derive(Arg)]
+            #[arg(fallible, static_args = ["serve"])]
+            struct Serve {
+                db: Db,
+                #[arg(range = 1..=10)]
+                level: u8,
+            }
+
+            let mut tracer = ArgTracer::new("Serve");
+            tracer
+                .try_add_arg_set(&Serve {
+                    db: Db { host: "h".into() },
+                    level: 3,
+                })
+                .unwrap();
+            assert_eq!(
+                traced(&tracer),
+                [
+                    ("serve", "Serve", TracedArgKind::Static),
+                    ("--host", "Serve.db.host", TracedArgKind::Name),
+                    ("h", "Serve.db.host", TracedArgKind::Value),
+                    ("--level", "Serve.level", TracedArgKind::Name),
+                    ("3", "Serve.level", TracedArgKind::Value),
+                ]
+            );
+
+            let result = tracer.try_add_arg_set(&Serve {
+                db: Db { host: "h".into() },
+                level: 11,
+            });
+            assert!(result.is_err());
+            assert_eq!(tracer.args().len(), 5);
+        }
+
+        #[test]
+        fn values_equal_to_names() {
+            #[derive(Arg)]
+            #[arg(schema)]
+            struct Filter {
+                exclude: Vec<&'static str>,
+            }
+
+            assert_eq!(
+                traced(&ArgTracer::of(&Filter {
+                    exclude: vec!["a", "--exclude"]
+                })),
+                [
+                    ("--exclude", "Filter.exclude", TracedArgKind::Name),
+                    ("a", "Filter.exclude", TracedArgKind::Value),
+                    ("--exclude", "Filter.exclude", TracedArgKind::Value),
+                ]
+            );
+        }
+    }
+
+    mod crate_path {
+        use argley::prelude::*;
+        use argley::CollectedArgs;
+
+        mod facade {
+            pub use ::argley as renamed;
+        }
+
+        #[test]
+        fn crate_path() {
+            #[derive(Arg)]
+            #[arg(fallible, crate = "self::facade::renamed", static_args = ["run"])]
+            struct Facaded {
+                verbose: bool,
+                #[arg(path = "absolute")]
+                dir: std::path::PathBuf,
+            }
+
+            let value = Facaded {
+                verbose: true,
+                dir: "/tmp".into(),
+            };
+
+            let result = value.try_collect_to::<CollectedArgs>().unwrap();
+            assert_eq!(&result[..], &["run", "--verbose", "--dir", "/tmp"]);
+        }
+    }
+
+    mod with_module {
+        use argley::prelude::*;
+        use argley::{ArgError, CollectedArgs};
+
+        mod key_value {
+            use argley::ArgConsumer;
+
+            pub fn add_to(value: &(&str, u8), name: &str, consumer: &mut impl ArgConsumer) -> bool {
+                consumer.add_arg(format!("{name}={}:{}", value.0, value.1));
+                true
+            }
+
+            pub fn add_unnamed_to(value: &(&str, u8), consumer: &mut impl ArgConsumer) -> bool {
+                consumer.add_arg(format!("{}:{}", value.0, value.1));
+                true
+            }
+        }
+
+        mod non_zero {
+            use argley::{ArgConsumer, ArgError};
+
+            pub fn add_to(
+                value: &u8,
+                name: &str,
+                consumer: &mut impl ArgConsumer,
+            ) -> Result<bool, ArgError> {
+                consumer.add_arg(name);
+                add_unnamed_to(value, consumer)
+            }
+
+            pub fn add_unnamed_to(
+                value: &u8,
+                consumer: &mut impl ArgConsumer,
+            ) -> Result<bool, ArgError> {
+                if *value == 0 {
+                    Err(ArgError::custom("must not be zero"))
+                } else {
+                    consumer.add_arg(value.to_string());
+                    Ok(true)
+                }
+            }
+        }
+
+        #[derive(Arg)]
+        struct Infallible {
+            #[arg(with = key_value)]
+            pair: (&'static str, u8),
+
+            #[arg(with = key_value, variadic)]
+            last: (&'static str, u8),
+        }
+
+        #[derive(Arg)]
+        #[arg(fallible)]
+        struct Fallible {
+            #[arg(with = non_zero)]
+            count: u8,
+
+            #[arg(with = key_value, position = 0)]
+            pair: (&'static str, u8),
+        }
+
+        #[test]
+        fn infallible() {
+            let source = Infallible {
+                pair: ("a", 1),
+                last: ("b", 2),
+            };
+
+            let result = source.collect_to::<CollectedArgs>();
+            assert_eq!(&result[..], &["--pair=a:1", "b:2"]);
+        }
+
+        #[test]
+        fn fallible() {
+            let source = Fallible {
+                count: 3,
+                pair: ("a", 1),
+            };
+            let result = source.try_collect_to::<CollectedArgs>().unwrap();
+            assert_eq!(&result[..], &["--count", "3", "a:1"]);
+
+            let source = Fallible {
+                count: 0,
+                pair: ("a", 1),
+            };
+            let error: ArgError = source.try_collect_to::<CollectedArgs>().unwrap_err();
+            assert_eq!(error.to_string(), "`count`: must not be zero");
+        }
+    }
+
+    #[test]
+    fn number_format() {
+        #[derive(Arg)]
+        struct NumberFormats {
+            #[arg(precision = 2)]
+            ratio: f64,
+
+            #[arg(radix = 16, prefix = "0x")]
+            mask: u32,
+
+            #[arg(radix = 8, width = 4, pad = '0')]
+            mode: u16,
+
+            #[arg(width = 4)]
+            #[arg(pad = '0')]
+            negative: i8,
+
+            #[arg(precision = 1)]
+            optional: Option<f32>,
+
+            #[arg(radix = 2)]
+            collection: Vec<u8>,
+        }
+
+        let source = NumberFormats {
+            ratio: 0.1 + 0.2,
+            mask: 255,
+            mode: 0o755,
+            negative: -5,
+            optional: Some(2.25),
+            collection: vec![1, 2],
+        };
+
+        let result = source.collect_to::<CollectedArgs>();
+        assert_eq!(
+            &result[..],
+            &[
+                "--ratio",
+                "0.30",
+                "--mask",
+                "0xff",
+                "--mode",
+                "0755",
+                "--negative",
+                "-005",
+                "--optional",
+                "2.2",
+                "--collection",
+                "1",
+                "10"
+            ]
+        );
+    }
+
+    #[test]
+    fn path_mode() {
+        use std::path::{Path, PathBuf};
+
+        #[derive(Arg)]
+        #[arg(fallible)]
+        struct Paths {
+            #[arg(path = "relative_to(self.cwd)")]
+            relative: PathBuf,
+
+            #[arg(path = "absolute")]
+            absolute: Vec<&'static Path>,
+
+            #[arg(path = "canonical")]
+            missing: Option<PathBuf>,
+
+            #[arg(path = "expand_home", position = 0)]
+            home: &'static Path,
+
+            #[arg(skip)]
+            cwd: PathBuf,
+        }
+
+        let source = Paths {
+            relative: "/srv/data/input.txt".into(),
+            absolute: vec![Path::new("foo/./bar")],
+            missing: None,
+            home: Path::new("~/.config"),
+            cwd: "/srv/work".into(),
+        };
+
+        let result = source.try_collect_to::<CollectedArgs>().unwrap();
+        let cwd = std::env::current_dir().unwrap();
+        let home = PathBuf::from(std::env::var_os("HOME").unwrap());
+
+        assert_eq!(
+            &result[..],
+            &[
+                OsStr::new("--relative"),
+                OsStr::new("../data/input.txt"),
+                OsStr::new("--absolute"),
+                cwd.join("foo/bar").as_os_str(),
+                home.join(".config").as_os_str(),
+            ]
+        );
+
+        let source = Paths {
+            missing: Some("/this/path/should/not/exist".into()),
+            ..source
+        };
+        let error = source.try_collect_to::<CollectedArgs>().unwrap_err();
+
+        assert_eq!(error.field_path().collect::<Vec<_>>(), ["missing"]);
+        assert!(
+            matches!(error.kind(), ArgErrorKind::Path { path, .. } if path == Path::new("/this/path/should/not/exist")),
+            "{error:?}"
+        );
+    }
+
+    #[test]
+    fn path_mode_parent_dirs() {
+        use argley::{Formatted, PathMode};
+        use std::path::Path;
+
+        let base = Path::new("/srv/work/../other/./nested/..");
+        let args = Formatted::new(
+            Path::new("/srv/data/../data/input.txt"),
+            PathMode::relative_to(base),
+        )
+        .try_collect_to::<CollectedArgs>()
+        .unwrap();
+        assert_eq!(&args[..], &[Path::new("../data/input.txt")]);
+
+        let args = Formatted::new(Path::new("/../srv/a/../b"), PathMode::Absolute)
+            .try_collect_to::<CollectedArgs>()
+            .unwrap();
+        assert_eq!(&args[..], &[Path::new("/srv/b")]);
+    }
+
+    mod validate {
+        use std::path::PathBuf;
+
+        use argley::prelude::*;
+        use argley::{ArgErrorKind, CollectedArgs};
+
+        #[derive(Arg)]
+        #[arg(fallible)]
+        struct Validated {
+            #[arg(range = 1..=65535)]
+            port: u32,
+
+            #[arg(non_empty, max_len = 3)]
+            name: String,
+
+            #[arg(one_of = ["fast", "slow"])]
+            modes: Vec<&'static str>,
+
+            #[arg(one_of = [1, 2])]
+            level: Option<u8>,
+
+            #[arg(exists)]
+            dir: Option<PathBuf>,
+        }
+
+        fn valid() -> Validated {
+            Validated {
+                port: 80,
+                name: "foo".into(),
+                modes: vec!["fast", "slow"],
+                level: None,
+                dir: Some(std::env::current_dir().unwrap()),
+            }
+        }
+
+        fn error_of(source: &Validated) -> (Vec<&'static str>, String, String) {
+            let error = source.try_collect_to::<CollectedArgs>().unwrap_err();
+            let path = error.field_path().collect();
+            match error.into_kind() {
+                ArgErrorKind::Invalid { value, reason } => (path, value, reason.into_owned()),
+                other => panic!("{other:?}"),
+            }
+        }
+
+        #[test]
+        fn ok() {
+            let result = valid().try_collect_to::<CollectedArgs>().unwrap();
+            assert_eq!(
+                &result[..7],
+                &["--port", "80", "--name", "foo", "--modes", "fast", "slow"]
+            );
+        }
+
+        #[test]
+        fn range() {
+            let source = Validated { port: 0, ..valid() };
+            let (path, value, _) = error_of(&source);
+            assert_eq!((&path[..], value.as_str()), (&["port"][..], "0"));
+        }
+
+        #[test]
+        fn non_empty() {
+            let source = Validated {
+                name: String::new(),
+                ..valid()
+            };
+            let (path, value, _) = error_of(&source);
+            assert_eq!((&path[..], value.as_str()), (&["name"][..], "\"\""));
+        }
+
+        #[test]
+        fn max_len() {
+            let source = Validated {
+                name: "food".into(),
+                ..valid()
+            };
+            let (path, _, reason) = error_of(&source);
+            assert_eq!(&path[..], &["name"]);
+            assert_eq!(reason, "expected a length of at most 3, got 4");
+        }
+
+        #[test]
+        fn one_of() {
+            let source = Validated {
+                modes: vec!["fast", "medium"],
+                ..valid()
+            };
+            let (path, value, reason) = error_of(&source);
+            assert_eq!((&path[..], value.as_str()), (&["modes"][..], "\"medium\""));
+            assert_eq!(reason, r#"expected one of ["fast", "slow"]"#);
+
+            let source = Validated {
+                level: Some(3),
+                ..valid()
+            };
+            let (path, value, _) = error_of(&source);
+            assert_eq!((&path[..], value.as_str()), (&["level"][..], "3"));
+        }
+
+        #[test]
+        fn exists() {
+            let source = Validated {
+                dir: Some("/this/path/should/not/exist".into()),
+                ..valid()
+            };
+            let (path, _, reason) = error_of(&source);
+            assert_eq!(&path[..], &["dir"]);
+            assert_eq!(reason, "path does not exist");
+        }
+    }
+
+    mod fallible {
+        use std::process::Command;
+
+        use argley::prelude::*;
+        use argley::{ArgError, CollectedArgs};
+
+        use super::Str;
+
+        fn parse_level(level: &Str) -> Result<u8, String> {
+            level.parse().map_err(|_| format!("invalid level: {level}"))
+        }
+
+        #[derive(Arg)]
+        #[arg(fallible, static_args = ["run"])]
+        struct Inner {
+            #[arg(formatter = parse_level)]
+            level: Str,
+        }
+
+        #[derive(Arg)]
+        #[arg(fallible)]
+        struct Outer {
+            name: Str,
+            inner: Inner,
+        }
+
+        #[derive(Arg)]
+        #[arg(fallible, drop_name)]
+        enum Wrapped {
+            Tuple(Str, Inner),
+
+            #[arg(value = &["unit"])]
+            Unit,
+        }
+
+        #[derive(Arg)]
+        struct Infallible {
+            value: Option<u8>,
+        }
+
+        #[test]
+        fn ok() {
+            let source = Outer {
+                name: "foo",
+                inner: Inner { level: "3" },
+            };
+
+            let result = source.try_collect_to::<CollectedArgs>().unwrap();
+            assert_eq!(
+                &result[..],
+                &["--name", "foo", "--inner", "run", "--level", "3"]
+            );
+        }
+
+        #[test]
+        fn field_path() {
+            let source = Outer {
+                name: "foo",
+                inner: Inner { level: "x" },
+            };
+
+            let error = source.try_collect_to::<CollectedArgs>().unwrap_err();
+            assert_eq!(error.field_path().collect::<Vec<_>>(), ["inner", "level"]);
+            assert_eq!(error.to_string(), "`inner.level`: invalid level: x");
+        }
+
+        #[test]
+        fn enums() {
+            let result = Wrapped::Tuple("a", Inner { level: "1" })
+                .try_collect_to::<CollectedArgs>()
+                .unwrap();
+            assert_eq!(&result[..], &["a", "run", "--level", "1"]);
+
+            let mut result = CollectedArgs::new();
+            assert!(Wrapped::Unit.try_add_to("--ignored", &mut result).unwrap());
+            assert_eq!(&result[..], &["unit"]);
+
+            let error = Wrapped::Tuple("a", Inner { level: "" })
+                .try_collect_to::<CollectedArgs>()
+                .unwrap_err();
+            assert_eq!(error.field_path().collect::<Vec<_>>(), ["1", "level"]);
+        }
+
+        #[test]
+        fn infallible_derive_implements_try_arg() {
+            let result = Infallible { value: Some(1) }
+                .try_collect_to::<CollectedArgs>()
+                .unwrap();
+            assert_eq!(&result[..], &["--value", "1"]);
+        }
+
+        #[test]
+        fn handwritten_arg_fields() {
+            struct Handwritten;
+
+            impl Arg for Handwritten {
+                fn add_unnamed_to(&self, consumer: &mut impl ArgConsumer) -> bool {
+                    consumer.add_arg("handwritten");
+                    true
+                }
+            }
+
+            #[derive(Arg)]
+            #[arg(fallible)]
+            struct Mixed {
+                inner: Inner,
+                handwritten: Handwritten,
+                many: Vec<Handwritten>,
+            }
+
+            let result = Mixed {
+                inner: Inner { level: "2" },
+                handwritten: Handwritten,
+                many: vec![Handwritten],
+            }
+            .try_collect_to::<CollectedArgs>()
+            .unwrap();
+            assert_eq!(
+                &result[..],
+                &[
+                    "--inner",
+                    "run",
+                    "--level",
+                    "2",
+                    "--handwritten",
+                    "handwritten",
+                    "--many",
+                    "handwritten"
+                ]
+            );
+        }
+
+        #[test]
+        fn try_add_arg_set_commits_nothing_on_error() {
+            let mut command = Command::new("foo");
+            command.arg("--first");
+
+            let error: ArgError = command
+                .try_add_arg_set(&Outer {
+                    name: "foo",
+                    inner: Inner { level: "x" },
+                })
+                .unwrap_err();
+            assert_eq!(error.field_path().collect::<Vec<_>>(), ["inner", "level"]);
+
+            let args = command.get_args().collect::<Vec<_>>();
+            assert_eq!(&args[..], &["--first"]);
+        }
+    }
+
+    mod constraints {
+        use argley::prelude::*;
+        use argley::{ArgErrorKind, CollectedArgs, ConstraintViolation};
+
+        use super::Str;
+
+        #[derive(Arg, Default)]
+        #[arg(fallible, required_one_of = ["input", "stdin"])]
+        struct Args {
+            #[arg(conflicts_with = "stdin")]
+            input: Option<Str>,
+            stdin: bool,
+
+            #[arg(requires = "user")]
+            password: Option<Str>,
+            user: Option<Str>,
+
+            #[arg(group = "output", required)]
+            json: bool,
+            #[arg(group = "output")]
+            yaml: bool,
+        }
+
+        fn violation(args: &Args) -> ConstraintViolation {
+            match args
+                .try_collect_to::<CollectedArgs>()
+                .unwrap_err()
+                .into_kind()
+            {
+                ArgErrorKind::Constraint(violation) => violation,
+                other => panic!("Unexpected error: {other}"),
+            }
+        }
+
+        #[test]
+        fn ok() {
+            let args = Args {
+                input: Some("in.txt"),
+                password: Some("hunter2"),
+                user: Some("admin"),
+                yaml: true,
+                ..Default::default()
+            };
+
+            let result = args.try_collect_to::<CollectedArgs>().unwrap();
+            assert_eq!(
+                &result[..],
+                &[
+                    "--input",
+                    "in.txt",
+                    "--password",
+                    "hunter2",
+                    "--user",
+                    "admin",
+                    "--yaml"
+                ]
+            );
+        }
+
+        #[test]
+        fn conflict() {
+            let args = Args {
+                input: Some("in.txt"),
+                stdin: true,
+                json: true,
+                ..Default::default()
+            };
+
+            let violation = violation(&args);
+            assert_eq!(
+                violation,
+                ConstraintViolation::Conflict {
+                    field: "input",
+                    other: "stdin"
+                }
+            );
+            assert_eq!(violation.to_string(), "`input` conflicts with `stdin`");
+        }
+
+        #[test]
+        fn violation_adds_nothing() {
+            #[derive(Arg)]
+            #[arg(fallible, static_args = ["run"])]
+            struct Flags {
+                #[arg(conflicts_with = "b")]
+                a: bool,
+                b: bool,
+            }
+
+            let mut out = CollectedArgs::new();
+            let error = Flags { a: true, b: true }.try_add_unnamed_to(&mut out);
+            assert!(error.is_err());
+            assert!(out.is_empty(), "{out:?}");
+        }
+
+        #[test]
+        fn emits_once() {
+            use std::sync::atomic::{AtomicUsize, Ordering};
+
+            static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+            fn counted(value: &u8) -> u8 {
+                CALLS.fetch_add(1, Ordering::Relaxed);
+                *value
+            }
+
+            #[derive(Arg)]
+            #[arg(fallible)]
+            struct Counted {
+                #[arg(formatter = counted, requires = "b")]
+                a: u8,
+                b: bool,
+            }
+
+            let args = Counted { a: 1, b: true }.try_collect_to::<CollectedArgs>();
+            assert_eq!(&args.unwrap()[..], &["--a", "1", "--b"]);
+            assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+
+            assert!(Counted { a: 1, b: false }
+                .try_collect_to::<CollectedArgs>()
+                .is_err());
+            assert_eq!(CALLS.load(Ordering::Relaxed), 2);
+        }
+
+        #[test]
+        fn requires() {
+            let args = Args {
+                stdin: true,
+                password: Some("hunter2"),
+                json: true,
+                ..Default::default()
+            };
+
+            assert_eq!(violation(&args).to_string(), "`password` requires `user`");
+        }
+
+        #[test]
+        fn required_one_of() {
+            let args = Args {
+                json: true,
+                ..Default::default()
+            };
+
+            assert_eq!(
+                violation(&args),
+                ConstraintViolation::Required {
+                    one_of: &["input", "stdin"]
+                }
+            );
+        }
+
+        #[test]
+        fn required_group() {
+            let args = Args {
+                stdin: true,
+                ..Default::default()
+            };
+
+            assert_eq!(
+                violation(&args).to_string(),
+                "one of `json`, `yaml` is required"
+            );
+        }
+
+        #[test]
+        fn required_field() {
+            #[derive(Arg)]
+            #[arg(fallible)]
+            struct Single {
+                #[arg(required)]
+                value: Option<u8>,
+            }
+
+            let error = Single { value: None }
+                .try_collect_to::<CollectedArgs>()
+                .unwrap_err();
+            assert_eq!(error.to_string(), "`value` is required");
+
+            let result = Single { value: Some(1) }
+                .try_collect_to::<CollectedArgs>()
+                .unwrap();
+            assert_eq!(&result[..], &["--value", "1"]);
+        }
+    }
+
     #[test]
     fn to_string() {
         #[derive(Arg, Display)]
@@ -246,6 +2370,7 @@ mod test {
             #[arg(value = &Nested(100))]
             ValuedUnit,
             Tuple(Str, #[arg(rename = "some-num")] u8, u8),
+            TupleSkip(#[arg(skip)] usize, Str, #[arg(skip)] u8, u8),
             Named {
                 #[arg(position = 0)]
                 a: Nested,
@@ -286,6 +2411,13 @@ mod test {
             assert_eq!(&result[..], &["--some-num", "0", "foo", "1"]);
         }
 
+        #[test]
+        fn tuple_skip() {
+            let mut result = CollectedArgs::new();
+            assert!(Foo::TupleSkip(10, "foo", 20, 1).add_unnamed_to(&mut result));
+            assert_eq!(&result[..], &["foo", "1"]);
+        }
+
         #[test]
         fn named_no_skip() {
             let mut result = CollectedArgs::new();
@@ -307,6 +2439,235 @@ mod test {
             assert_eq!(&result[..], &["100"]);
         }
 
+        #[test]
+        fn value_enum() {
+            #[derive(Arg)]
+            #[arg(value_enum)]
+            enum Format {
+                Json,
+                YamlStream,
+
+                #[arg(value = "text")]
+                PlainText,
+            }
+
+            #[derive(Arg)]
+            #[arg(value_enum, rename_all = "SCREAMING_SNAKE_CASE")]
+            enum Level {
+                MostVerbose,
+            }
+
+            #[derive(Arg)]
+            struct Args {
+                format: Format,
+                formats: Vec<Format>,
+                level: Level,
+            }
+
+            let source = Args {
+                format: Format::Json,
+                formats: vec![Format::YamlStream, Format::PlainText],
+                level: Level::MostVerbose,
+            };
+
+            let result = source.collect_to::<CollectedArgs>();
+            assert_eq!(
+                &result[..],
+                &[
+                    "--format",
+                    "json",
+                    "--formats",
+                    "yaml-stream",
+                    "text",
+                    "--level",
+                    "MOST_VERBOSE"
+                ]
+            );
+        }
+
+        #[test]
+        fn subcommand() {
+            #[derive(Arg)]
+            #[arg(subcommand)]
+            enum Docker {
+                Compose(Compose),
+                Ps,
+                ImagePrune {
+                    #[arg(short)]
+                    a: bool,
+                },
+            }
+
+            #[derive(Arg)]
+            #[arg(subcommand, rename_all = "UPPERCASE")]
+            enum Compose {
+                Up {
+                    detach: bool,
+
+                    #[arg(variadic)]
+                    services: Vec<Str>,
+                },
+            }
+
+            #[derive(Arg)]
+            struct Args {
+                debug: bool,
+                command: Docker,
+            }
+
+            let source = Args {
+                debug: true,
+                command: Docker::Compose(Compose::Up {
+                    detach: true,
+                    services: vec!["web"],
+                }),
+            };
+            let result = source.collect_to::<CollectedArgs>();
+            assert_eq!(
+                &result[..],
+                &["--debug", "compose", "UP", "--detach", "web"]
+            );
+
+            #[derive(Arg)]
+            #[arg(subcommand)]
+            enum UnitsOnly {
+                Status,
+            }
+            assert_eq!(
+                &UnitsOnly::Status.collect_to::<CollectedArgs>()[..],
+                &["status"]
+            );
+
+            let mut result = CollectedArgs::new();
+            assert!(Docker::Ps.add_to("--ignored", &mut result));
+            assert!(Docker::ImagePrune { a: true }.add_unnamed_to(&mut result));
+            assert_eq!(&result[..], &["ps", "image-prune", "-a"]);
+        }
+
+        #[test]
+        fn global() {
+            #[derive(Arg)]
+            #[arg(subcommand)]
+            enum GitCmd {
+                Commit {
+                    #[arg(short)]
+                    m: Str,
+                },
+            }
+
+            #[derive(Arg)]
+            struct Git {
+                verbose: bool,
+
+                #[arg(subcommand)]
+                command: GitCmd,
+
+                #[arg(global, short, rename = "C")]
+                dir: Option<Str>,
+
+                #[arg(global)]
+                no_pager: bool,
+            }
+
+            let source = Git {
+                verbose: true,
+                command: GitCmd::Commit { m: "msg" },
+                dir: Some("repo"),
+                no_pager: true,
+            };
+            let result = source.collect_to::<CollectedArgs>();
+            assert_eq!(
+                &result[..],
+                &[
+                    "-C",
+                    "repo",
+                    "--no_pager",
+                    "commit",
+                    "-m",
+                    "msg",
+                    "--verbose"
+                ]
+            );
+        }
+
+        #[test]
+        fn variant_opts() {
+            #[derive(Arg)]
+            #[arg(subcommand, static_args = ["--no-color"])]
+            enum Cargo {
+                #[arg(rename = "b", static_args = ["--locked"], short)]
+                Build { j: u8 },
+
+                #[arg(skip)]
+                Internal(u8),
+            }
+
+            #[derive(Arg)]
+            enum Mode {
+                #[arg(drop_name)]
+                Raw(Str),
+                Level(u8),
+
+                #[arg(skip)]
+                Off,
+            }
+
+            let result = Cargo::Build { j: 4 }.collect_to::<CollectedArgs>();
+            assert_eq!(&result[..], &["--no-color", "b", "--locked", "-j", "4"]);
+
+            let mut result = CollectedArgs::new();
+            assert!(Mode::Raw("raw").add_to("--mode", &mut result));
+            assert!(Mode::Level(3).add_to("--mode", &mut result));
+            assert!(!Mode::Off.add_to("--mode", &mut result));
+            assert_eq!(&result[..], &["raw", "--mode", "3"]);
+
+            let result = Cargo::Internal(1).collect_to::<CollectedArgs>();
+            assert_eq!(&result[..], &["--no-color"]);
+        }
+
+        #[test]
+        fn flag_enum() {
+            #[derive(Arg)]
+            #[arg(flag_enum)]
+            enum Compression {
+                Gzip,
+                Bzip2,
+                ZStandard,
+
+                #[arg(rename = "4", short)]
+                Lz4Fast,
+                Level(u8),
+            }
+
+            #[derive(Arg)]
+            #[arg(flag_enum, short, rename_all = "lowercase")]
+            enum Verbosity {
+                Q,
+                V,
+            }
+
+            #[derive(Arg)]
+            struct Args {
+                compression: Compression,
+                verbosity: Option<Verbosity>,
+            }
+
+            let source = Args {
+                compression: Compression::ZStandard,
+                verbosity: Some(Verbosity::V),
+            };
+            let result = source.collect_to::<CollectedArgs>();
+            assert_eq!(&result[..], &["--z-standard", "-v"]);
+
+            let result = [
+                Compression::Gzip,
+                Compression::Lz4Fast,
+                Compression::Level(9),
+            ]
+            .collect_to::<CollectedArgs>();
+            assert_eq!(&result[..], &["--gzip", "-4", "--level", "9"]);
+        }
+
         #[test]
         fn as_repr() {
             #[derive(Arg)]
@@ -321,5 +2682,33 @@ mod test {
             let result = AsReprEnum::B.collect_to::<CollectedArgs>();
             assert_eq!(&result[..], &["20"]);
         }
+
+        #[test]
+        fn as_repr_non_copy() {
+            #[derive(Arg)]
+            #[arg(as_repr)]
+            #[repr(i16)]
+            enum NonCopy {
+                _A = -5,
+                B,
+                C = 7,
+            }
+
+            let result = [NonCopy::B, NonCopy::C].collect_to::<CollectedArgs>();
+            assert_eq!(&result[..], &["-4", "7"]);
+        }
+
+        #[test]
+        fn discriminants() {
+            #[derive(Arg)]
+            #[arg(value_enum)]
+            enum Level {
+                Low = 1,
+                High = 10,
+            }
+
+            let result = [Level::Low, Level::High].collect_to::<CollectedArgs>();
+            assert_eq!(&result[..], &["low", "high"]);
+        }
     }
 }
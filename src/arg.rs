@@ -5,7 +5,135 @@ use crate::ArgConsumer;
 pub use argley_macro::Arg;
 
 /// An argument that can be passed to an [`ArgConsumer`] such as a [`Command`](std::process::Command).
+///
+/// # Rejected derive options
+///
+/// The [derive macro](argley_macro::Arg) errors on options that conflict or have no effect.
+/// Fields can't share a position:
+///
+/// ```compile_fail
+/// # use argley::prelude::*;
+/// #[derive(Arg)]
+/// struct Args {
+///     #[arg(position = 0)]
+///     input: String,
+///     #[arg(position = 0)]
+///     output: String,
+/// }
+/// ```
+///
+/// Nor an emitted name:
+///
+/// ```compile_fail
+/// # use argley::prelude::*;
+/// #[derive(Arg)]
+/// struct Args {
+///     #[arg(rename = "out")]
+///     output: String,
+///     #[arg(rename = "out")]
+///     target: String,
+/// }
+/// ```
+///
+/// There can be at most one variadic field:
+///
+/// ```compile_fail
+/// # use argley::prelude::*;
+/// #[derive(Arg)]
+/// struct Args {
+///     #[arg(variadic)]
+///     inputs: Vec<String>,
+///     #[arg(variadic)]
+///     outputs: Vec<String>,
+/// }
+/// ```
+///
+/// `short` & `rename` have no effect on positional fields:
+///
+/// ```compile_fail
+/// # use argley::prelude::*;
+/// #[derive(Arg)]
+/// struct Args {
+///     #[arg(position = 0, short)]
+///     input: String,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use argley::prelude::*;
+/// #[derive(Arg)]
+/// struct Args {
+///     #[arg(position = 0, rename = "in")]
+///     input: String,
+/// }
+/// ```
+///
+/// Neither does `precision` on integers nor `radix` on floats:
+///
+/// ```compile_fail
+/// # use argley::prelude::*;
+/// #[derive(Arg)]
+/// struct Args {
+///     #[arg(precision = 2)]
+///     jobs: u8,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use argley::prelude::*;
+/// #[derive(Arg)]
+/// struct Args {
+///     #[arg(radix = 16)]
+///     ratio: f64,
+/// }
+/// ```
+///
+/// `bound` can't be combined with `to_string` or `as_repr`, which don't use the fields' impls:
+///
+/// ```compile_fail
+/// # use argley::prelude::*;
+/// # use std::fmt;
+/// #[derive(Arg)]
+/// #[arg(to_string, bound = "T: fmt::Display")]
+/// struct Name<T>(T);
+///
+/// impl<T: fmt::Display> fmt::Display for Name<T> {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         self.0.fmt(f)
+///     }
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use argley::prelude::*;
+/// #[derive(Arg, Clone, Copy)]
+/// #[arg(as_repr, bound = "")]
+/// #[repr(u8)]
+/// enum Level {
+///     Low = 1,
+/// }
+/// ```
+///
+/// Unknown options get reported along with the valid ones:
+///
+/// ```compile_fail
+/// # use argley::prelude::*;
+/// #[derive(Arg)]
+/// struct Args {
+///     #[arg(renam = "in")]
+///     input: String,
+/// }
+/// ```
 pub trait Arg {
+    /// Whether a value of the type may add nothing, e.g. `false`, [`None`] or an empty
+    /// collection. Describes fields in their [`FieldSchema`](crate::FieldSchema).
+    const OPTIONAL: bool = false;
+
+    /// Whether [`add_to`](Arg::add_to) ignores the name it gets passed, e.g. for `drop_name`,
+    /// `subcommand` & `flag_enum` types. Lets [`ArgSchema`](crate::ArgSchema) leave out names
+    /// that never get output.
+    const DROPS_NAME: bool = false;
+
     /// Add a named argument to the given [`ArgConsumer`]. Similar to
     /// [`add_unnamed_to`](Arg::add_unnamed_to), but includes the name of the property if
     /// applicable.
@@ -33,7 +161,7 @@ pub trait Arg {
     /// assert_eq!(&args[..], &["--arg1", "foo", "--arg2", "bar", "--arg5"]);
     /// ```
     fn add_to(&self, name: &str, consumer: &mut impl ArgConsumer) -> bool {
-        consumer.add_arg(name);
+        consumer.add_name(name);
         self.add_unnamed_to(consumer)
     }
 
@@ -89,3 +217,25 @@ pub trait Arg {
         consumer
     }
 }
+
+/// Adds a `formatter`'s output in infallible derives. Called with method syntax so that the
+/// output gets auto-dereferenced, letting formatters return e.g. `&Inner` for an `Inner` that
+/// only implements [`Arg`] itself.
+#[doc(hidden)]
+pub trait AddFormatterOutput {
+    fn add_output_to(&self, name: &str, consumer: &mut impl ArgConsumer) -> bool;
+
+    fn add_output_unnamed_to(&self, consumer: &mut impl ArgConsumer) -> bool;
+}
+
+impl<T: ?Sized + Arg> AddFormatterOutput for T {
+    #[inline]
+    fn add_output_to(&self, name: &str, consumer: &mut impl ArgConsumer) -> bool {
+        self.add_to(name, consumer)
+    }
+
+    #[inline]
+    fn add_output_unnamed_to(&self, consumer: &mut impl ArgConsumer) -> bool {
+        self.add_unnamed_to(consumer)
+    }
+}
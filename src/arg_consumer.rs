@@ -1,4 +1,4 @@
-use crate::Arg;
+use crate::{Arg, ArgError, TryArg};
 use std::ffi::{OsStr, OsString};
 use std::process::Command;
 
@@ -13,6 +13,14 @@ pub trait ArgConsumer {
     /// Add multiple arguments
     fn add_args(&mut self, args: impl IntoIterator<Item = impl AsRef<OsStr>>) -> &mut Self;
 
+    /// Add the name an argument gets output with, e.g. `--jobs` or a subcommand. Called by
+    /// [`Arg::add_to`] & derived implementations; adds the name via [`add_arg`](Self::add_arg) by
+    /// default.
+    #[inline]
+    fn add_name(&mut self, name: impl AsRef<OsStr>) -> &mut Self {
+        self.add_arg(name)
+    }
+
     /// Add a set of arguments from an [`Arg`] implementation
     #[inline]
     fn add_arg_set(&mut self, args: &impl Arg) -> &mut Self
@@ -22,6 +30,140 @@ pub trait ArgConsumer {
         args.add_unnamed_to(self);
         self
     }
+
+    /// Add a set of arguments from a [`TryArg`] implementation. Nothing gets added if an error
+    /// occurs: the consumer's methods get called only once all the arguments have been produced.
+    ///
+    /// # Errors
+    ///
+    /// Propagated from [`TryArg::try_add_unnamed_to`]
+    fn try_add_arg_set(&mut self, args: &impl TryArg) -> Result<&mut Self, ArgError>
+    where
+        Self: Sized,
+    {
+        let mut buffer = ArgBuffer::default();
+        args.try_add_unnamed_to(&mut buffer)?;
+        buffer.replay(self);
+        Ok(self)
+    }
+
+    /// Add a container's or variant's `static_args`. Called by derived implementations; adds the
+    /// arguments via [`add_args`](Self::add_args) by default.
+    #[inline]
+    fn add_static_args(&mut self, args: impl IntoIterator<Item = impl AsRef<OsStr>>) -> &mut Self {
+        self.add_args(args)
+    }
+
+    /// Called by derived implementations before a field adds its arguments. `field` is its name
+    /// in the source, or its index for tuple fields, & `name` the name it gets output with, if
+    /// any. Does nothing by default.
+    #[inline]
+    #[allow(unused_variables)]
+    fn enter_field(&mut self, field: &'static str, name: Option<&'static str>) {}
+
+    /// Called by derived implementations after a field has added its arguments. Not called if
+    /// the field fails to produce them. Does nothing by default.
+    #[inline]
+    fn exit_field(&mut self) {}
+
+    /// Called by derived implementations of enums before a variant adds its arguments. `variant`
+    /// is its name in the source & `name` the subcommand or flag name it gets output with, if
+    /// any. Does nothing by default.
+    #[inline]
+    #[allow(unused_variables)]
+    fn enter_variant(&mut self, variant: &'static str, name: Option<&'static str>) {}
+
+    /// Called by derived implementations of enums after a variant has added its arguments. Not
+    /// called if the variant fails to produce them. Does nothing by default.
+    #[inline]
+    fn exit_variant(&mut self) {}
+}
+
+/// Consumer recording the calls made to it so they can be replayed on another consumer. Not part
+/// of the public API.
+#[doc(hidden)]
+#[derive(Default)]
+pub struct ArgBuffer(Vec<Call>);
+
+enum Call {
+    Arg(OsString),
+    Args(Vec<OsString>),
+    Name(OsString),
+    StaticArgs(Vec<OsString>),
+    EnterField(&'static str, Option<&'static str>),
+    ExitField,
+    EnterVariant(&'static str, Option<&'static str>),
+    ExitVariant,
+}
+
+impl ArgBuffer {
+    /// Make the recorded calls on `consumer`
+    pub fn replay<C: ArgConsumer>(self, consumer: &mut C) {
+        for call in self.0 {
+            match call {
+                Call::Arg(arg) => {
+                    consumer.add_arg(arg);
+                }
+                Call::Args(args) => {
+                    consumer.add_args(args);
+                }
+                Call::Name(name) => {
+                    consumer.add_name(name);
+                }
+                Call::StaticArgs(args) => {
+                    consumer.add_static_args(args);
+                }
+                Call::EnterField(field, name) => consumer.enter_field(field, name),
+                Call::ExitField => consumer.exit_field(),
+                Call::EnterVariant(variant, name) => consumer.enter_variant(variant, name),
+                Call::ExitVariant => consumer.exit_variant(),
+            }
+        }
+    }
+}
+
+impl ArgConsumer for ArgBuffer {
+    fn add_arg(&mut self, arg: impl AsRef<OsStr>) -> &mut Self {
+        self.0.push(Call::Arg(arg.as_ref().to_owned()));
+        self
+    }
+
+    fn add_args(&mut self, args: impl IntoIterator<Item = impl AsRef<OsStr>>) -> &mut Self {
+        let args = args.into_iter().map(move |arg| arg.as_ref().to_owned());
+        self.0.push(Call::Args(args.collect()));
+        self
+    }
+
+    fn add_name(&mut self, name: impl AsRef<OsStr>) -> &mut Self {
+        self.0.push(Call::Name(name.as_ref().to_owned()));
+        self
+    }
+
+    fn add_static_args(&mut self, args: impl IntoIterator<Item = impl AsRef<OsStr>>) -> &mut Self {
+        let args = args.into_iter().map(move |arg| arg.as_ref().to_owned());
+        self.0.push(Call::StaticArgs(args.collect()));
+        self
+    }
+
+    #[inline]
+    fn enter_field(&mut self, field: &'static str, name: Option<&'static str>) {
+        self.0.push(Call::EnterField(field, name));
+    }
+
+    #[inline]
+    fn exit_field(&mut self) {
+        self.0.push(Call::ExitField);
+    }
+
+    #[inline]
+    fn enter_variant(&mut self, variant: &'static str, name: Option<&'static str>) {
+        self.0.push(Call::EnterVariant(variant, name));
+    }
+
+    #[inline]
+    fn exit_variant(&mut self) {
+        self.0.push(Call::ExitVariant);
+    }
 }
 
 macro_rules! command_arg_consumer {
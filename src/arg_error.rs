@@ -0,0 +1,244 @@
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+/// Error returned by [`TryArg`](crate::TryArg) implementations.
+///
+/// Keeps track of the path of the field it originated from, e.g. an error raised by the `host`
+/// field of a struct nested in the `db` field of the outer struct displays as
+/// ``` `db.host`: <message> ```.
+#[derive(Debug)]
+pub struct ArgError {
+    kind: ArgErrorKind,
+
+    /// Innermost field first
+    field_path: Vec<&'static str>,
+}
+
+/// The kind of an [`ArgError`]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ArgErrorKind {
+    /// A path argument failed to resolve; see [`PathMode`](crate::PathMode).
+    Path {
+        /// The path that failed to resolve
+        path: PathBuf,
+
+        /// The underlying error
+        source: io::Error,
+    },
+
+    /// A value failed validation; see [`validate`](crate::validate).
+    Invalid {
+        /// Debug representation of the offending value
+        value: String,
+
+        /// Why the value is invalid
+        reason: Cow<'static, str>,
+    },
+
+    /// A cross-field constraint of a derived struct was violated, e.g. two conflicting fields
+    /// were both set.
+    Constraint(ConstraintViolation),
+
+    /// An I/O error
+    Io(io::Error),
+
+    /// Any other error, e.g. one returned from a formatter
+    Custom(Box<dyn Error + Send + Sync>),
+}
+
+/// A violated cross-field constraint; see [`ArgErrorKind::Constraint`]. Fields are identified by
+/// their names in the struct definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConstraintViolation {
+    /// `field` and `other` were both emitted
+    Conflict {
+        /// The field declaring the conflict
+        field: &'static str,
+
+        /// The field it conflicts with
+        other: &'static str,
+    },
+
+    /// `field` was emitted without `required`
+    Requires {
+        /// The field declaring the requirement
+        field: &'static str,
+
+        /// The field it requires
+        required: &'static str,
+    },
+
+    /// None of the fields were emitted
+    Required {
+        /// The fields at least one of which must be emitted
+        one_of: &'static [&'static str],
+    },
+}
+
+impl ArgError {
+    /// Create a new error of the given kind
+    #[inline]
+    #[must_use]
+    pub fn new(kind: ArgErrorKind) -> Self {
+        Self {
+            kind,
+            field_path: Vec::new(),
+        }
+    }
+
+    /// Create a new [`Custom`](ArgErrorKind::Custom) error
+    #[inline]
+    pub fn custom(error: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        Self::new(ArgErrorKind::Custom(error.into()))
+    }
+
+    /// The kind of this error
+    #[inline]
+    #[must_use]
+    pub fn kind(&self) -> &ArgErrorKind {
+        &self.kind
+    }
+
+    /// Consume the error, returning its kind
+    #[inline]
+    #[must_use]
+    pub fn into_kind(self) -> ArgErrorKind {
+        self.kind
+    }
+
+    /// Path of the field the error originated from, outermost field first. Empty if the error
+    /// didn't originate from a field.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argley::ArgError;
+    /// let error = ArgError::custom("oops").in_field("host").in_field("db");
+    ///
+    /// assert_eq!(error.field_path().collect::<Vec<_>>(), ["db", "host"]);
+    /// assert_eq!(error.to_string(), "`db.host`: oops");
+    /// ```
+    pub fn field_path(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.field_path.iter().rev().copied()
+    }
+
+    /// Mark the error as having originated from inside the given field. Called on the way out,
+    /// so the outermost field gets added last.
+    #[must_use]
+    pub fn in_field(mut self, field: &'static str) -> Self {
+        self.field_path.push(field);
+        self
+    }
+}
+
+impl Display for ArgError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if !self.field_path.is_empty() {
+            f.write_str("`")?;
+            for (idx, field) in self.field_path().enumerate() {
+                if idx != 0 {
+                    f.write_str(".")?;
+                }
+                f.write_str(field)?;
+            }
+            f.write_str("`: ")?;
+        }
+
+        Display::fmt(&self.kind, f)
+    }
+}
+
+impl Display for ArgErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Path {
+                ref path,
+                ref source,
+            } => write!(f, "unable to resolve path {}: {}", path.display(), source),
+            Self::Invalid {
+                ref value,
+                ref reason,
+            } => write!(f, "invalid value {value}: {reason}"),
+            Self::Constraint(ref violation) => Display::fmt(violation, f),
+            Self::Io(ref e) => Display::fmt(e, f),
+            Self::Custom(ref e) => Display::fmt(e, f),
+        }
+    }
+}
+
+impl Display for ConstraintViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Conflict { field, other } => write!(f, "`{field}` conflicts with `{other}`"),
+            Self::Requires { field, required } => write!(f, "`{field}` requires `{required}`"),
+            Self::Required { one_of: [field] } => write!(f, "`{field}` is required"),
+            Self::Required { one_of } => {
+                f.write_str("one of ")?;
+                for (idx, field) in one_of.iter().enumerate() {
+                    if idx != 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "`{field}`")?;
+                }
+                f.write_str(" is required")
+            }
+        }
+    }
+}
+
+impl Error for ArgError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self.kind {
+            ArgErrorKind::Path { ref source, .. } | ArgErrorKind::Io(ref source) => Some(source),
+            ArgErrorKind::Custom(ref e) => e.source(),
+            ArgErrorKind::Invalid { .. } | ArgErrorKind::Constraint(_) => None,
+        }
+    }
+}
+
+impl From<ArgErrorKind> for ArgError {
+    #[inline]
+    fn from(kind: ArgErrorKind) -> Self {
+        Self::new(kind)
+    }
+}
+
+impl From<io::Error> for ArgError {
+    #[inline]
+    fn from(e: io::Error) -> Self {
+        Self::new(ArgErrorKind::Io(e))
+    }
+}
+
+impl From<Box<dyn Error + Send + Sync>> for ArgError {
+    #[inline]
+    fn from(e: Box<dyn Error + Send + Sync>) -> Self {
+        Self::new(ArgErrorKind::Custom(e))
+    }
+}
+
+impl From<String> for ArgError {
+    #[inline]
+    fn from(e: String) -> Self {
+        Self::custom(e)
+    }
+}
+
+impl From<&'static str> for ArgError {
+    #[inline]
+    fn from(e: &'static str) -> Self {
+        Self::custom(e)
+    }
+}
+
+impl From<std::convert::Infallible> for ArgError {
+    #[inline]
+    fn from(e: std::convert::Infallible) -> Self {
+        match e {}
+    }
+}
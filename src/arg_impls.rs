@@ -1,12 +1,13 @@
 use std::borrow::Cow;
-use std::collections::{BTreeSet, HashSet, LinkedList, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque};
 use std::ffi::{OsStr, OsString};
+use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::Arc;
 
 use crate::arg_consumer::ArgConsumer;
-use crate::Arg;
+use crate::{Arg, FormatArg, MapFormat};
 
 // ========== MACROS ==========
 
@@ -65,6 +66,9 @@ macro_rules! impl_slice_body {
 
 macro_rules! impl_deref {
     (body) => {
+        const OPTIONAL: bool = T::OPTIONAL;
+        const DROPS_NAME: bool = T::DROPS_NAME;
+
         #[inline]
         fn add_to(&self, name: &str, consumer: &mut impl ArgConsumer) -> bool {
             Arg::add_to(&**self, name, consumer)
@@ -77,20 +81,38 @@ macro_rules! impl_deref {
     };
     ($($ty: ty) +) => {
         $(
-            impl<T: Arg> Arg for $ty {
+            impl<T: ?Sized + Arg> Arg for $ty {
                 impl_deref!(body);
             }
         )+
     };
 }
 
+macro_rules! impl_map {
+    (body) => {
+        const OPTIONAL: bool = true;
+
+        #[inline]
+        fn add_to(&self, name: &str, consumer: &mut impl ArgConsumer) -> bool {
+            self.add_formatted_to(&MapFormat::new(), name, consumer)
+        }
+
+        #[inline]
+        fn add_unnamed_to(&self, consumer: &mut impl ArgConsumer) -> bool {
+            self.add_formatted_unnamed_to(&MapFormat::new(), consumer)
+        }
+    };
+}
+
 macro_rules! impl_iter {
     (body => $check: ident) => {
+        const OPTIONAL: bool = true;
+
         fn add_to(&self, name: &str, consumer: &mut impl ArgConsumer) -> bool {
             if self.$check() {
                false
             } else {
-                consumer.add_arg(name);
+                consumer.add_name(name);
                 process_iter(self, consumer)
             }
         }
@@ -114,7 +136,7 @@ macro_rules! impl_iter {
 
 // ========== MACRO_CALLS ==========
 
-impl_to_string!(i8 u8 i16 u16 i32 u32 i64 u64 i128 u128 isize usize f32 f64);
+impl_to_string!(i8 u8 i16 u16 i32 u32 i64 u64 i128 u128 isize usize f32 f64 char);
 impl_direct!([String, str] [PathBuf, Path] [OsString, OsStr]);
 impl_deref!(Box<T> Rc<T> Arc<T>);
 impl_iter!([is_empty] => [T] BTreeSet<T> LinkedList<T> VecDeque<T>);
@@ -122,10 +144,14 @@ impl_iter!([is_empty] => [T] BTreeSet<T> LinkedList<T> VecDeque<T>);
 // ========== CUSTOM_IMPLS ==========
 
 impl<T: Arg> Arg for Vec<T> {
+    const OPTIONAL: bool = true;
+
     impl_slice_body!();
 }
 
 impl<T: Arg, const N: usize> Arg for [T; N] {
+    const OPTIONAL: bool = N == 0;
+
     impl_slice_body!();
 }
 
@@ -138,6 +164,9 @@ impl<'a, T: ?Sized + Arg + ToOwned + 'a> Arg for Cow<'a, T> {
 }
 
 impl<T: Arg> Arg for Option<T> {
+    const OPTIONAL: bool = true;
+    const DROPS_NAME: bool = T::DROPS_NAME;
+
     fn add_to(&self, name: &str, consumer: &mut impl ArgConsumer) -> bool {
         if let Some(value) = self {
             Arg::add_to(value, name, consumer)
@@ -155,10 +184,37 @@ impl<T: Arg> Arg for Option<T> {
     }
 }
 
+/// Outputs the entries sorted by key; see [`MapFormat`].
+impl<K: Arg + Ord, V: Arg, S> Arg for HashMap<K, V, S> {
+    impl_map!(body);
+}
+
+/// See [`MapFormat`].
+impl<K: Arg, V: Arg> Arg for BTreeMap<K, V> {
+    impl_map!(body);
+}
+
+/// Outputs nothing, allowing marker fields in derived types.
+impl<T: ?Sized> Arg for PhantomData<T> {
+    const OPTIONAL: bool = true;
+
+    #[inline]
+    fn add_to(&self, _: &str, _: &mut impl ArgConsumer) -> bool {
+        false
+    }
+
+    #[inline]
+    fn add_unnamed_to(&self, _: &mut impl ArgConsumer) -> bool {
+        false
+    }
+}
+
 impl Arg for bool {
+    const OPTIONAL: bool = true;
+
     fn add_to(&self, name: &str, consumer: &mut impl ArgConsumer) -> bool {
         if *self {
-            consumer.add_arg(name);
+            consumer.add_name(name);
             true
         } else {
             false
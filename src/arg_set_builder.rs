@@ -0,0 +1,72 @@
+use crate::{Arg, ArgConsumer, ArgError, TryArg};
+
+/// Builders generated by the [`ArgBuilder` derive](argley_macro::ArgBuilder). Lets a builder add
+/// the struct it builds straight to a consumer if the struct implements [`Arg`] or [`TryArg`].
+///
+/// # Example
+///
+/// ```
+/// # use argley::prelude::*;
+/// # use argley::CollectedArgs;
+/// #[derive(Arg, ArgBuilder, Default)]
+/// struct Args {
+///     verbose: bool,
+///     level: Option<u8>,
+/// }
+///
+/// let mut args = CollectedArgs::new();
+/// Args::builder().level(3).add_arg_set(&mut args);
+///
+/// assert_eq!(&args[..], &["--level", "3"]);
+///
+/// let args = Args::builder().level(3).clear_level().build();
+/// assert_eq!(args.level, None);
+/// ```
+///
+/// Fields whose setters would shadow one of the builder's methods are rejected:
+///
+/// ```compile_fail
+/// # use argley::prelude::*;
+/// #[derive(ArgBuilder, Default)]
+/// struct Args {
+///     build: bool,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use argley::prelude::*;
+/// #[derive(ArgBuilder, Default)]
+/// struct Args {
+///     level: Option<u8>,
+///     clear_level: bool,
+/// }
+/// ```
+pub trait ArgSetBuilder: Sized {
+    /// The struct being built
+    type Output;
+
+    /// Finish building the struct
+    fn build(self) -> Self::Output;
+
+    /// Build the struct & add it to the consumer via [`ArgConsumer::add_arg_set`]
+    #[inline]
+    fn add_arg_set<C: ArgConsumer>(self, consumer: &mut C) -> &mut C
+    where
+        Self::Output: Arg,
+    {
+        consumer.add_arg_set(&self.build())
+    }
+
+    /// Build the struct & add it to the consumer via [`ArgConsumer::try_add_arg_set`]
+    ///
+    /// # Errors
+    ///
+    /// Propagated from [`TryArg::try_add_unnamed_to`]
+    #[inline]
+    fn try_add_arg_set<C: ArgConsumer>(self, consumer: &mut C) -> Result<&mut C, ArgError>
+    where
+        Self::Output: TryArg,
+    {
+        consumer.try_add_arg_set(&self.build())
+    }
+}
@@ -0,0 +1,335 @@
+use std::ffi::{OsStr, OsString};
+use std::fmt::{Display, Formatter};
+
+use crate::{Arg, ArgConsumer, ArgError, TryArg};
+
+/// Option changed between two argument sets, as listed by [`ArgDiff`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgChange {
+    /// An option only present in the new arguments
+    Added {
+        /// The option's name, e.g. `--jobs`, or the field's path for fields output without one
+        option: String,
+
+        /// The option's values, excluding its name
+        value: Vec<OsString>,
+    },
+
+    /// An option only present in the old arguments
+    Removed {
+        /// The option's name, e.g. `--jobs`, or the field's path for fields output without one
+        option: String,
+
+        /// The option's values, excluding its name
+        value: Vec<OsString>,
+    },
+
+    /// An option present in both with different values
+    Changed {
+        /// The option's name, e.g. `--jobs`, or the field's path for fields output without one
+        option: String,
+
+        /// The old values, excluding the option's name
+        old: Vec<OsString>,
+
+        /// The new values, excluding the option's name
+        new: Vec<OsString>,
+    },
+}
+
+/// Differences between two argument sets at the level of options & positionals rather than
+/// individual arguments. [`Display`]s as a report with a line per change.
+///
+/// # Example
+///
+/// ```
+/// # use argley::prelude::*;
+/// # use argley::ArgDiff;
+/// #[derive(Arg, Clone)]
+/// struct Args {
+///     jobs: Option<u8>,
+///     verbose: bool,
+///     release: bool,
+///     #[arg(position = 0)]
+///     input: String,
+/// }
+///
+/// let old = Args { jobs: Some(4), verbose: true, release: false, input: "a.txt".into() };
+/// let new = Args { jobs: Some(8), verbose: false, release: true, ..old.clone() };
+///
+/// assert_eq!(
+///     ArgDiff::between(&old, &new).to_string(),
+///     "~ --jobs: 4 -> 8\n- --verbose\n+ --release\n"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ArgDiff {
+    changes: Vec<ArgChange>,
+}
+
+impl ArgDiff {
+    /// Compare the arguments of two values, grouping them by the field that produced them.
+    /// Fields of nested derived types are compared individually.
+    pub fn between<T: Arg + ?Sized>(old: &T, new: &T) -> Self {
+        let mut old_fields = FieldRecorder::default();
+        old.add_unnamed_to(&mut old_fields);
+        let mut new_fields = FieldRecorder::default();
+        new.add_unnamed_to(&mut new_fields);
+
+        Self::compare(old_fields.options(), new_fields.options())
+    }
+
+    /// [`between`](Self::between) for [`TryArg`] implementations
+    ///
+    /// # Errors
+    ///
+    /// Propagated from [`TryArg::try_add_unnamed_to`]
+    pub fn try_between<T: TryArg + ?Sized>(old: &T, new: &T) -> Result<Self, ArgError> {
+        let mut old_fields = FieldRecorder::default();
+        old.try_add_unnamed_to(&mut old_fields)?;
+        let mut new_fields = FieldRecorder::default();
+        new.try_add_unnamed_to(&mut new_fields)?;
+
+        Ok(Self::compare(old_fields.options(), new_fields.options()))
+    }
+
+    /// Compare two lists of already collected arguments, e.g.
+    /// [`CollectedArgs`](crate::CollectedArgs). Without the fields to go by, any argument
+    /// starting with `-` that isn't a number is taken to be an option name & the arguments
+    /// following it its values. Arguments preceding the first option are compared by position &
+    /// listed as `#0`, `#1` etc.
+    pub fn between_collected<S: AsRef<OsStr>>(old: &[S], new: &[S]) -> Self {
+        Self::compare(split_options(old), split_options(new))
+    }
+
+    /// The changes in the order of the old arguments, followed by the added options
+    #[inline]
+    pub fn changes(&self) -> &[ArgChange] {
+        &self.changes
+    }
+
+    /// `true` if the arguments are equivalent
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    fn compare(old: Vec<(String, Vec<OsString>)>, mut new: Vec<(String, Vec<OsString>)>) -> Self {
+        let mut changes = Vec::new();
+
+        for (option, old) in old {
+            match new.iter().position(|(key, _)| *key == option) {
+                Some(idx) => {
+                    let (_, new) = new.remove(idx);
+                    if old != new {
+                        changes.push(ArgChange::Changed { option, old, new });
+                    }
+                }
+                None => changes.push(ArgChange::Removed { option, value: old }),
+            }
+        }
+
+        changes.extend(
+            new.into_iter()
+                .map(move |(option, value)| ArgChange::Added { option, value }),
+        );
+
+        Self { changes }
+    }
+}
+
+impl ArgChange {
+    /// The name or path of the changed option
+    pub fn option(&self) -> &str {
+        match *self {
+            Self::Added { ref option, .. }
+            | Self::Removed { ref option, .. }
+            | Self::Changed { ref option, .. } => option,
+        }
+    }
+}
+
+impl Display for ArgChange {
+    /// `+ --option values`, `- --option values` or `~ --option: old -> new`
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Added {
+                ref option,
+                ref value,
+            } => write!(f, "+ {option}{}", Values(value)),
+            Self::Removed {
+                ref option,
+                ref value,
+            } => write!(f, "- {option}{}", Values(value)),
+            Self::Changed {
+                ref option,
+                ref old,
+                ref new,
+            } => write!(f, "~ {option}:{} ->{}", Values(old), Values(new)),
+        }
+    }
+}
+
+impl Display for ArgDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Values, each preceded by a space
+struct Values<'a>(&'a [OsString]);
+
+impl Display for Values<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for value in self.0 {
+            write!(f, " {}", value.to_string_lossy())?;
+        }
+        Ok(())
+    }
+}
+
+/// Consumer grouping arguments by the innermost field that produced them
+struct FieldRecorder {
+    /// Starts with a root entry for arguments added outside of any field, e.g. `static_args`
+    entries: Vec<Entry>,
+    open: Vec<usize>,
+}
+
+struct Entry {
+    path: String,
+    name: Option<&'static str>,
+    /// Values, excluding the field's own name
+    args: Vec<OsString>,
+    /// Whether the field added anything, including its name
+    added: bool,
+}
+
+impl Default for FieldRecorder {
+    fn default() -> Self {
+        Self {
+            entries: vec![Entry {
+                path: String::new(),
+                name: None,
+                args: Vec::new(),
+                added: false,
+            }],
+            open: Vec::new(),
+        }
+    }
+}
+
+impl FieldRecorder {
+    /// `(option, values)` pairs of the fields that added arguments, keyed by name or path
+    fn options(self) -> Vec<(String, Vec<OsString>)> {
+        let mut out: Vec<(String, Vec<OsString>)> = Vec::new();
+
+        for entry in self.entries {
+            if !entry.added {
+                continue;
+            }
+
+            let key = match entry.name {
+                Some(name) => name.to_owned(),
+                None if entry.path.is_empty() => "(args)".to_owned(),
+                None => entry.path,
+            };
+            // A field can be entered several times, e.g. nested structs in a collection
+            match out.iter_mut().find(|(existing, _)| *existing == key) {
+                Some((_, existing)) => existing.extend(entry.args),
+                None => out.push((key, entry.args)),
+            }
+        }
+
+        out
+    }
+
+    /// The innermost open field, or the root entry outside of any field
+    fn current(&mut self) -> &mut Entry {
+        let idx = self.open.last().copied().unwrap_or(0);
+        let entry = &mut self.entries[idx];
+        entry.added = true;
+        entry
+    }
+}
+
+impl ArgConsumer for FieldRecorder {
+    fn add_arg(&mut self, arg: impl AsRef<OsStr>) -> &mut Self {
+        self.current().args.push(arg.as_ref().to_owned());
+        self
+    }
+
+    fn add_args(&mut self, args: impl IntoIterator<Item = impl AsRef<OsStr>>) -> &mut Self {
+        let current = self.current();
+        current
+            .args
+            .extend(args.into_iter().map(move |arg| arg.as_ref().to_owned()));
+        self
+    }
+
+    /// The field's own name is left out of its values
+    fn add_name(&mut self, name: impl AsRef<OsStr>) -> &mut Self {
+        let entry = self.current();
+        if entry.name.map_or(true, |own| name.as_ref() != own) {
+            entry.args.push(name.as_ref().to_owned());
+        }
+        self
+    }
+
+    fn enter_field(&mut self, field: &'static str, name: Option<&'static str>) {
+        let path = match self.open.last() {
+            Some(&parent) => format!("{}.{field}", self.entries[parent].path),
+            None => field.to_owned(),
+        };
+
+        self.open.push(self.entries.len());
+        self.entries.push(Entry {
+            path,
+            name,
+            args: Vec::new(),
+            added: false,
+        });
+    }
+
+    #[inline]
+    fn exit_field(&mut self) {
+        self.open.pop();
+    }
+}
+
+/// Split collected arguments into leading positionals & options
+fn split_options<S: AsRef<OsStr>>(args: &[S]) -> Vec<(String, Vec<OsString>)> {
+    let mut out: Vec<(String, Vec<OsString>)> = Vec::new();
+    let mut current = None;
+    let mut position = 0;
+
+    for arg in args {
+        let arg = arg.as_ref();
+        if is_option_name(arg) {
+            let key = arg.to_string_lossy().into_owned();
+            current = Some(
+                match out.iter().position(|(existing, _)| *existing == key) {
+                    Some(idx) => idx,
+                    None => {
+                        out.push((key, Vec::new()));
+                        out.len() - 1
+                    }
+                },
+            );
+        } else if let Some(idx) = current {
+            out[idx].1.push(arg.to_owned());
+        } else {
+            out.push((format!("#{position}"), vec![arg.to_owned()]));
+            position += 1;
+        }
+    }
+
+    out
+}
+
+fn is_option_name(arg: &OsStr) -> bool {
+    let arg = arg.to_string_lossy();
+    arg.len() > 1 && arg.starts_with('-') && arg.parse::<f64>().is_err()
+}
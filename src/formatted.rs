@@ -0,0 +1,313 @@
+use std::collections::{BTreeSet, HashSet, LinkedList, VecDeque};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::arg_consumer::ArgConsumer;
+use crate::{Arg, ArgError, TryArg};
+
+/// A value that can be added to an [`ArgConsumer`] according to a format specification `F`,
+/// e.g. a [`NumberFormat`](crate::NumberFormat).
+///
+/// Implemented for the leaf types a specification knows how to render as well as for the
+/// standard wrappers & collections of such types, so a spec applied to an `Option<f64>` or
+/// `Vec<u8>` gets applied to each contained value.
+///
+/// Only implemented for specifications that can't fail; ones that can, such as
+/// [`PathMode`](crate::PathMode), only implement [`TryFormatArg`].
+pub trait FormatArg<F: ?Sized> {
+    /// Formatted counterpart of [`Arg::add_to`].
+    fn add_formatted_to(&self, format: &F, name: &str, consumer: &mut impl ArgConsumer) -> bool {
+        consumer.add_name(name);
+        self.add_formatted_unnamed_to(format, consumer)
+    }
+
+    /// Formatted counterpart of [`Arg::add_unnamed_to`].
+    fn add_formatted_unnamed_to(&self, format: &F, consumer: &mut impl ArgConsumer) -> bool;
+}
+
+/// Fallible counterpart of [`FormatArg`], implemented for every specification.
+pub trait TryFormatArg<F: ?Sized> {
+    /// Formatted counterpart of [`TryArg::try_add_to`].
+    ///
+    /// # Errors
+    ///
+    /// Specification-specific
+    fn try_add_formatted_to(
+        &self,
+        format: &F,
+        name: &str,
+        consumer: &mut impl ArgConsumer,
+    ) -> Result<bool, ArgError> {
+        consumer.add_name(name);
+        self.try_add_formatted_unnamed_to(format, consumer)
+    }
+
+    /// Formatted counterpart of [`TryArg::try_add_unnamed_to`].
+    ///
+    /// # Errors
+    ///
+    /// Specification-specific
+    fn try_add_formatted_unnamed_to(
+        &self,
+        format: &F,
+        consumer: &mut impl ArgConsumer,
+    ) -> Result<bool, ArgError>;
+}
+
+/// Pairs a value with a format specification, turning it into a [`TryArg`] &, if the value
+/// implements [`FormatArg`] of it, an [`Arg`].
+///
+/// # Example
+///
+/// ```
+/// # use argley::prelude::*;
+/// # use argley::{CollectedArgs, Formatted, NumberFormat};
+/// let values = vec![Some(1.0_f64), None, Some(0.125)];
+/// let format = NumberFormat::new().precision(2);
+///
+/// let args = Formatted::new(&values, format).collect_to::<CollectedArgs>();
+/// assert_eq!(&args[..], &["1.00", "0.12"]);
+/// ```
+pub struct Formatted<'a, T: ?Sized, F> {
+    value: &'a T,
+    format: F,
+}
+
+impl<'a, T: ?Sized, F> Formatted<'a, T, F> {
+    /// Pair the value with the given format
+    #[inline]
+    pub fn new(value: &'a T, format: F) -> Self {
+        Self { value, format }
+    }
+}
+
+impl<T: ?Sized + FormatArg<F>, F> Arg for Formatted<'_, T, F> {
+    #[inline]
+    fn add_to(&self, name: &str, consumer: &mut impl ArgConsumer) -> bool {
+        self.value.add_formatted_to(&self.format, name, consumer)
+    }
+
+    #[inline]
+    fn add_unnamed_to(&self, consumer: &mut impl ArgConsumer) -> bool {
+        self.value.add_formatted_unnamed_to(&self.format, consumer)
+    }
+}
+
+impl<T: ?Sized + TryFormatArg<F>, F> TryArg for Formatted<'_, T, F> {
+    #[inline]
+    fn try_add_to(&self, name: &str, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+        self.value
+            .try_add_formatted_to(&self.format, name, consumer)
+    }
+
+    #[inline]
+    fn try_add_unnamed_to(&self, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+        self.value
+            .try_add_formatted_unnamed_to(&self.format, consumer)
+    }
+}
+
+// ========== MACROS ==========
+
+macro_rules! impl_deref {
+    ($($ty: ty) +) => {
+        $(
+            impl<F: ?Sized, T: ?Sized + FormatArg<F>> FormatArg<F> for $ty {
+                #[inline]
+                fn add_formatted_to(&self, format: &F, name: &str, consumer: &mut impl ArgConsumer) -> bool {
+                    T::add_formatted_to(&**self, format, name, consumer)
+                }
+
+                #[inline]
+                fn add_formatted_unnamed_to(&self, format: &F, consumer: &mut impl ArgConsumer) -> bool {
+                    T::add_formatted_unnamed_to(&**self, format, consumer)
+                }
+            }
+
+            impl<F: ?Sized, T: ?Sized + TryFormatArg<F>> TryFormatArg<F> for $ty {
+                #[inline]
+                fn try_add_formatted_to(&self, format: &F, name: &str, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+                    T::try_add_formatted_to(&**self, format, name, consumer)
+                }
+
+                #[inline]
+                fn try_add_formatted_unnamed_to(&self, format: &F, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+                    T::try_add_formatted_unnamed_to(&**self, format, consumer)
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! impl_iter {
+    (body) => {
+        fn add_formatted_to(&self, format: &F, name: &str, consumer: &mut impl ArgConsumer) -> bool {
+            if self.is_empty() {
+                false
+            } else {
+                consumer.add_name(name);
+                process_iter(self, format, consumer)
+            }
+        }
+
+        fn add_formatted_unnamed_to(&self, format: &F, consumer: &mut impl ArgConsumer) -> bool {
+            if self.is_empty() {
+                false
+            } else {
+                process_iter(self, format, consumer)
+            }
+        }
+    };
+    (try_body) => {
+        fn try_add_formatted_to(&self, format: &F, name: &str, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+            if self.is_empty() {
+                Ok(false)
+            } else {
+                consumer.add_name(name);
+                try_process_iter(self, format, consumer)
+            }
+        }
+
+        fn try_add_formatted_unnamed_to(&self, format: &F, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+            if self.is_empty() {
+                Ok(false)
+            } else {
+                try_process_iter(self, format, consumer)
+            }
+        }
+    };
+    ($($ty: ty) +) => {
+        $(
+            impl<F: ?Sized, T: FormatArg<F>> FormatArg<F> for $ty {
+                impl_iter!(body);
+            }
+
+            impl<F: ?Sized, T: TryFormatArg<F>> TryFormatArg<F> for $ty {
+                impl_iter!(try_body);
+            }
+        )+
+    };
+}
+
+// ========== MACRO_CALLS ==========
+
+impl_deref!(&T Box<T> Rc<T> Arc<T>);
+impl_iter!([T] Vec<T> BTreeSet<T> LinkedList<T> VecDeque<T>);
+
+// ========== CUSTOM_IMPLS ==========
+
+impl<F: ?Sized, T: FormatArg<F>, const N: usize> FormatArg<F> for [T; N] {
+    #[inline]
+    fn add_formatted_to(&self, format: &F, name: &str, consumer: &mut impl ArgConsumer) -> bool {
+        self[..].add_formatted_to(format, name, consumer)
+    }
+
+    #[inline]
+    fn add_formatted_unnamed_to(&self, format: &F, consumer: &mut impl ArgConsumer) -> bool {
+        self[..].add_formatted_unnamed_to(format, consumer)
+    }
+}
+
+impl<F: ?Sized, T: TryFormatArg<F>, const N: usize> TryFormatArg<F> for [T; N] {
+    #[inline]
+    fn try_add_formatted_to(
+        &self,
+        format: &F,
+        name: &str,
+        consumer: &mut impl ArgConsumer,
+    ) -> Result<bool, ArgError> {
+        self[..].try_add_formatted_to(format, name, consumer)
+    }
+
+    #[inline]
+    fn try_add_formatted_unnamed_to(
+        &self,
+        format: &F,
+        consumer: &mut impl ArgConsumer,
+    ) -> Result<bool, ArgError> {
+        self[..].try_add_formatted_unnamed_to(format, consumer)
+    }
+}
+
+impl<F: ?Sized, T: FormatArg<F>, S> FormatArg<F> for HashSet<T, S> {
+    impl_iter!(body);
+}
+
+impl<F: ?Sized, T: TryFormatArg<F>, S> TryFormatArg<F> for HashSet<T, S> {
+    impl_iter!(try_body);
+}
+
+impl<F: ?Sized, T: FormatArg<F>> FormatArg<F> for Option<T> {
+    fn add_formatted_to(&self, format: &F, name: &str, consumer: &mut impl ArgConsumer) -> bool {
+        if let Some(value) = self {
+            value.add_formatted_to(format, name, consumer)
+        } else {
+            false
+        }
+    }
+
+    fn add_formatted_unnamed_to(&self, format: &F, consumer: &mut impl ArgConsumer) -> bool {
+        if let Some(value) = self {
+            value.add_formatted_unnamed_to(format, consumer)
+        } else {
+            false
+        }
+    }
+}
+
+impl<F: ?Sized, T: TryFormatArg<F>> TryFormatArg<F> for Option<T> {
+    fn try_add_formatted_to(
+        &self,
+        format: &F,
+        name: &str,
+        consumer: &mut impl ArgConsumer,
+    ) -> Result<bool, ArgError> {
+        if let Some(value) = self {
+            value.try_add_formatted_to(format, name, consumer)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn try_add_formatted_unnamed_to(
+        &self,
+        format: &F,
+        consumer: &mut impl ArgConsumer,
+    ) -> Result<bool, ArgError> {
+        if let Some(value) = self {
+            value.try_add_formatted_unnamed_to(format, consumer)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+// ========== HELPERS ==========
+
+fn process_iter<'a, F, E, I>(iter: I, format: &F, consumer: &mut impl ArgConsumer) -> bool
+where
+    F: ?Sized,
+    E: FormatArg<F> + 'a,
+    I: IntoIterator<Item = &'a E>,
+{
+    for element in iter {
+        element.add_formatted_unnamed_to(format, consumer);
+    }
+    true
+}
+
+fn try_process_iter<'a, F, E, I>(
+    iter: I,
+    format: &F,
+    consumer: &mut impl ArgConsumer,
+) -> Result<bool, ArgError>
+where
+    F: ?Sized,
+    E: TryFormatArg<F> + 'a,
+    I: IntoIterator<Item = &'a E>,
+{
+    for element in iter {
+        element.try_add_formatted_unnamed_to(format, consumer)?;
+    }
+    Ok(true)
+}
@@ -0,0 +1,647 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque};
+use std::error::Error;
+use std::ffi::{OsStr, OsString};
+use std::fmt::{Display, Formatter};
+use std::hash::{BuildHasher, Hash};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+#[cfg(feature = "derive")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "derive")))]
+pub use argley_macro::FromArgs;
+
+/// Types that can be parsed back from the arguments they produce. Implemented by the
+/// [`FromArgs` derive](argley_macro::FromArgs).
+///
+/// # Example
+///
+/// ```
+/// # use argley::prelude::*;
+/// # use argley::CollectedArgs;
+/// #[derive(Arg, FromArgs, Debug, PartialEq)]
+/// struct Args {
+///     verbose: bool,
+///     name: String,
+///     #[arg(position = 0)]
+///     input: String,
+/// }
+///
+/// let args = Args { verbose: false, name: "--verbose".into(), input: "in".into() };
+/// let collected = args.collect_to::<CollectedArgs>();
+///
+/// assert_eq!(&collected[..], &["--name", "--verbose", "in"]);
+/// assert_eq!(Args::from_args(&collected).unwrap(), args);
+/// ```
+///
+/// # Ambiguous shapes
+///
+/// Structs whose output can't be told apart fail to compile. Positional `bool`s never get output:
+///
+/// ```compile_fail
+/// # use argley::prelude::*;
+/// #[derive(Arg, FromArgs)]
+/// struct Args {
+///     #[arg(position = 0)]
+///     force: bool,
+/// }
+/// ```
+///
+/// There can be at most one optional or multi-value positional field:
+///
+/// ```compile_fail
+/// # use argley::prelude::*;
+/// #[derive(Arg, FromArgs)]
+/// struct Args {
+///     #[arg(position = 0)]
+///     first: Option<String>,
+///     #[arg(variadic)]
+///     rest: Vec<String>,
+/// }
+/// ```
+///
+/// A multi-value named field can't be followed by an optional or multi-value positional one:
+///
+/// ```compile_fail
+/// # use argley::prelude::*;
+/// #[derive(Arg, FromArgs)]
+/// struct Args {
+///     tags: Vec<String>,
+///     #[arg(position = 0)]
+///     input: Option<String>,
+/// }
+/// ```
+///
+/// Neither can several optional or multi-value fields output without a name, e.g. a `drop_name`
+/// struct & an optional positional field:
+///
+/// ```compile_fail
+/// # use argley::prelude::*;
+/// #[derive(Arg, FromArgs)]
+/// #[arg(drop_name)]
+/// struct Db {
+///     host: Option<String>,
+/// }
+///
+/// #[derive(Arg, FromArgs)]
+/// struct Args {
+///     db: Db,
+///     #[arg(position = 0)]
+///     input: Option<String>,
+/// }
+/// ```
+pub trait FromArgs: Sized {
+    /// Parse the arguments, e.g. ones [collected](crate::CollectedArgs) from an instance of the
+    /// type.
+    ///
+    /// # Errors
+    ///
+    /// If the arguments don't describe a valid instance of the type; see [`FromArgsError`].
+    fn from_args<S: AsRef<OsStr>>(args: &[S]) -> Result<Self, FromArgsError>;
+}
+
+/// Types that can be parsed from the values following a field's name, or from positional
+/// arguments.
+pub trait FromArgValue: Sized {
+    /// How many values the type consumes
+    const ARITY: Arity = Arity::One;
+
+    /// Whether the type gets output without the field's name, e.g. `drop_name` types. Its values
+    /// then get read from the arguments following the fields before it.
+    const DROPS_NAME: bool = false;
+
+    /// Parse the values. Gets exactly one value for [`Arity::One`] & [`Arity::Optional`] & none
+    /// for [`Arity::Flag`].
+    ///
+    /// # Errors
+    ///
+    /// If one of the values is invalid
+    fn from_values(values: &[&OsStr]) -> Result<Self, InvalidValue>;
+
+    /// The value to use if the field is absent from the arguments. `None` makes the field
+    /// required.
+    #[inline]
+    fn absent() -> Option<Self> {
+        None
+    }
+}
+
+/// How many values a [`FromArgValue`] consumes
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Arity {
+    /// None; the field's presence is its value, e.g. a `bool`
+    Flag,
+
+    /// A single value
+    One,
+
+    /// At most one value, e.g. an `Option`. The type's [`absent`](FromArgValue::absent) value
+    /// gets used when there is none.
+    Optional,
+
+    /// Any number of values, e.g. a `Vec`
+    Many,
+
+    /// Any number of values, each preceded by the field's name, e.g. a map
+    Repeated,
+}
+
+/// A value a [`FromArgValue`] failed to parse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidValue {
+    /// The offending value
+    pub value: OsString,
+
+    /// Why it's invalid
+    pub reason: String,
+}
+
+/// Error returned by [`FromArgs::from_args`]. Fields are identified by their names in the struct
+/// definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FromArgsError {
+    /// One of the container's `static_args` is missing
+    MissingStaticArg {
+        /// The expected argument
+        expected: &'static str,
+    },
+
+    /// A required field is absent
+    MissingField {
+        /// The absent field
+        field: &'static str,
+    },
+
+    /// A field's name isn't followed by its value
+    MissingValue {
+        /// The field whose value is missing
+        field: &'static str,
+    },
+
+    /// A field's value failed to parse
+    InvalidValue {
+        /// The field the value belongs to
+        field: &'static str,
+
+        /// The offending value
+        value: OsString,
+
+        /// Why it's invalid
+        reason: String,
+    },
+
+    /// An argument didn't match any field
+    Unexpected {
+        /// The argument
+        arg: OsString,
+    },
+}
+
+impl Display for FromArgsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::MissingStaticArg { expected } => write!(f, "expected `{expected}`"),
+            Self::MissingField { field } => write!(f, "`{field}` is required"),
+            Self::MissingValue { field } => write!(f, "`{field}` is missing its value"),
+            Self::InvalidValue {
+                field,
+                ref value,
+                ref reason,
+            } => write!(f, "`{field}`: invalid value {value:?}: {reason}"),
+            Self::Unexpected { ref arg } => write!(f, "unexpected argument {arg:?}"),
+        }
+    }
+}
+
+impl Error for FromArgsError {}
+
+/// Describes the error of a nested [`FromArgs`] type
+impl From<FromArgsError> for InvalidValue {
+    fn from(e: FromArgsError) -> Self {
+        let value = match e {
+            FromArgsError::InvalidValue { ref value, .. }
+            | FromArgsError::Unexpected { arg: ref value } => value.clone(),
+            _ => OsString::new(),
+        };
+
+        Self {
+            value,
+            reason: e.to_string(),
+        }
+    }
+}
+
+/// Argument parser driven by code generated by the [`FromArgs` derive](argley_macro::FromArgs).
+/// Not part of the public API.
+///
+/// Reads the arguments left to right in the order the [`Arg` derive](argley_macro::Arg) outputs
+/// them: `static_args`, named fields in declaration order, positional fields by position & the
+/// variadic field. Each field consumes as many values as its [`Arity`] says, so values are never
+/// mistaken for names.
+#[doc(hidden)]
+pub struct ArgParser<'a> {
+    args: Vec<&'a OsStr>,
+    pos: usize,
+}
+
+impl<'a> ArgParser<'a> {
+    /// Consume the `static_args` the arguments must start with
+    pub fn new<S: AsRef<OsStr>>(
+        args: &'a [S],
+        static_args: &'static [&'static str],
+    ) -> Result<Self, FromArgsError> {
+        let args = args.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+
+        for (idx, &expected) in static_args.iter().enumerate() {
+            if args.get(idx).copied() != Some(OsStr::new(expected)) {
+                return Err(FromArgsError::MissingStaticArg { expected });
+            }
+        }
+
+        Ok(Self {
+            args,
+            pos: static_args.len(),
+        })
+    }
+
+    /// Panic if the named field at `idx` makes the output ambiguous. `drops_name` says which named
+    /// fields are output without their name. Evaluated at compile time.
+    pub const fn check_named(named: &[Arity], drops_name: &[bool], idx: usize) {
+        if drops_name[idx] {
+            check_unnamed(named, drops_name, idx, named[idx]);
+        }
+    }
+
+    /// Panic if the positional field at `idx`, in output order, makes the output ambiguous.
+    /// Evaluated at compile time.
+    pub const fn check_positional(
+        named: &[Arity],
+        drops_name: &[bool],
+        positional: &[Arity],
+        idx: usize,
+    ) {
+        let arity = positional[idx];
+        if matches!(arity, Arity::Flag) {
+            panic!("`FromArgs` doesn't support positional flags as they never get output");
+        }
+        if is_variable(arity) {
+            let mut before = 0;
+            while before < idx {
+                if is_variable(positional[before]) {
+                    panic!("`FromArgs` can't tell apart several optional or multi-value positional fields");
+                }
+                before += 1;
+            }
+        }
+        check_unnamed(named, drops_name, named.len(), arity);
+    }
+
+    /// Parse a named field. Fields taking several values stop at the name of a field output
+    /// after them, `later`, or when only `reserved` arguments remain for the fields output
+    /// without a name after them. Fields whose type drops the name get parsed from the next
+    /// arguments instead.
+    pub fn named<T: FromArgValue>(
+        &mut self,
+        name: &str,
+        field: &'static str,
+        later: &[&str],
+        reserved: usize,
+    ) -> Result<T, FromArgsError> {
+        if T::DROPS_NAME {
+            return self.unnamed(field, later, reserved);
+        }
+        if self
+            .args
+            .get(self.pos)
+            .map_or(true, move |&arg| arg != name)
+        {
+            return T::absent().ok_or(FromArgsError::MissingField { field });
+        }
+        if self.remaining() <= reserved {
+            // The name is one of the later fields' values
+            return T::absent().ok_or(FromArgsError::MissingValue { field });
+        }
+        self.pos += 1;
+
+        let start = self.pos;
+        match T::ARITY {
+            Arity::Flag => {}
+            Arity::One | Arity::Optional => {
+                if self.pos == self.args.len() {
+                    return Err(FromArgsError::MissingValue { field });
+                }
+                self.pos += 1;
+            }
+            Arity::Many => {
+                while self.remaining() > reserved && !self.next_is(later) {
+                    self.pos += 1;
+                }
+            }
+            Arity::Repeated => {
+                if self.pos == self.args.len() {
+                    return Err(FromArgsError::MissingValue { field });
+                }
+                let mut values = vec![self.args[self.pos]];
+                self.pos += 1;
+                while self.remaining() > reserved + 1 && self.args[self.pos] == name {
+                    values.push(self.args[self.pos + 1]);
+                    self.pos += 2;
+                }
+                return parse(field, &values);
+            }
+        }
+
+        parse(field, &self.args[start..self.pos])
+    }
+
+    /// Parse a positional field from the next arguments, leaving `reserved` for the positional
+    /// fields after it
+    pub fn positional<T: FromArgValue>(
+        &mut self,
+        field: &'static str,
+        reserved: usize,
+    ) -> Result<T, FromArgsError> {
+        let available = self.remaining().saturating_sub(reserved);
+        let take = match T::ARITY {
+            Arity::Flag => 0,
+            Arity::One if self.remaining() == 0 => {
+                return Err(FromArgsError::MissingField { field });
+            }
+            Arity::One => 1,
+            Arity::Optional => available.min(1),
+            Arity::Many | Arity::Repeated => available,
+        };
+        if take == 0 {
+            if let Some(value) = T::absent() {
+                return Ok(value);
+            }
+        }
+
+        let start = self.pos;
+        self.pos += take;
+        parse(field, &self.args[start..self.pos])
+    }
+
+    /// Error on any arguments that weren't consumed
+    pub fn finish(self) -> Result<(), FromArgsError> {
+        match self.args.get(self.pos) {
+            Some(&arg) => Err(FromArgsError::Unexpected {
+                arg: arg.to_owned(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Parse a field output without its name from the next arguments, stopping at `later` names
+    fn unnamed<T: FromArgValue>(
+        &mut self,
+        field: &'static str,
+        later: &[&str],
+        reserved: usize,
+    ) -> Result<T, FromArgsError> {
+        let max = match T::ARITY {
+            Arity::Flag => 0,
+            Arity::One | Arity::Optional => 1,
+            Arity::Many | Arity::Repeated => usize::MAX,
+        };
+
+        let start = self.pos;
+        while self.pos - start < max && self.remaining() > reserved && !self.next_is(later) {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            if let Some(value) = T::absent() {
+                return Ok(value);
+            }
+            if T::ARITY == Arity::One {
+                return Err(FromArgsError::MissingField { field });
+            }
+        }
+
+        parse(field, &self.args[start..self.pos])
+    }
+
+    fn next_is(&self, names: &[&str]) -> bool {
+        let next = self.args[self.pos];
+        names.iter().any(move |&name| next == name)
+    }
+
+    fn remaining(&self) -> usize {
+        self.args.len() - self.pos
+    }
+}
+
+// ========== MACROS ==========
+
+macro_rules! impl_from_str {
+    ($($ty:ty)+) => {
+        $(
+            impl FromArgValue for $ty {
+                fn from_values(values: &[&OsStr]) -> Result<Self, InvalidValue> {
+                    let value = single(values)?;
+                    let str = value.to_str().ok_or_else(|| InvalidValue {
+                        value: value.to_owned(),
+                        reason: "not valid UTF-8".into(),
+                    })?;
+
+                    <$ty>::from_str(str).map_err(move |e| InvalidValue {
+                        value: value.to_owned(),
+                        reason: e.to_string(),
+                    })
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! impl_collection {
+    (body) => {
+        const ARITY: Arity = Arity::Many;
+
+        fn from_values(values: &[&OsStr]) -> Result<Self, InvalidValue> {
+            values
+                .iter()
+                .map(move |value| T::from_values(&[*value]))
+                .collect()
+        }
+
+        #[inline]
+        fn absent() -> Option<Self> {
+            Some(Self::default())
+        }
+    };
+    ($($ty: ty)+) => {
+        $(
+            impl<T: FromArgValue> FromArgValue for $ty {
+                impl_collection!(body);
+            }
+        )+
+    };
+}
+
+macro_rules! impl_map {
+    (body) => {
+        const ARITY: Arity = Arity::Repeated;
+
+        fn from_values(values: &[&OsStr]) -> Result<Self, InvalidValue> {
+            values
+                .iter()
+                .map(move |&entry| parse_entry(entry))
+                .collect()
+        }
+
+        #[inline]
+        fn absent() -> Option<Self> {
+            Some(Self::default())
+        }
+    };
+}
+
+// ========== MACRO_CALLS ==========
+
+impl_from_str!(i8 u8 i16 u16 i32 u32 i64 u64 i128 u128 isize usize f32 f64 char String);
+impl_collection!(Vec<T> VecDeque<T> LinkedList<T>);
+
+// ========== CUSTOM_IMPLS ==========
+
+impl FromArgValue for OsString {
+    #[inline]
+    fn from_values(values: &[&OsStr]) -> Result<Self, InvalidValue> {
+        single(values).map(ToOwned::to_owned)
+    }
+}
+
+impl FromArgValue for PathBuf {
+    #[inline]
+    fn from_values(values: &[&OsStr]) -> Result<Self, InvalidValue> {
+        single(values).map(PathBuf::from)
+    }
+}
+
+/// Present when its name is
+impl FromArgValue for bool {
+    const ARITY: Arity = Arity::Flag;
+
+    #[inline]
+    fn from_values(_: &[&OsStr]) -> Result<Self, InvalidValue> {
+        Ok(true)
+    }
+
+    #[inline]
+    fn absent() -> Option<Self> {
+        Some(false)
+    }
+}
+
+impl<T: FromArgValue> FromArgValue for Option<T> {
+    const ARITY: Arity = match T::ARITY {
+        Arity::One => Arity::Optional,
+        arity => arity,
+    };
+
+    #[inline]
+    fn from_values(values: &[&OsStr]) -> Result<Self, InvalidValue> {
+        T::from_values(values).map(Some)
+    }
+
+    #[inline]
+    fn absent() -> Option<Self> {
+        Some(None)
+    }
+}
+
+impl<T: FromArgValue + Ord> FromArgValue for BTreeSet<T> {
+    impl_collection!(body);
+}
+
+impl<T: FromArgValue + Eq + Hash, S: BuildHasher + Default> FromArgValue for HashSet<T, S> {
+    impl_collection!(body);
+}
+
+/// Parses the entries output by the default [`MapFormat`](crate::MapFormat), splitting them at
+/// the first `=`
+impl<K: FromArgValue + Ord, V: FromArgValue> FromArgValue for BTreeMap<K, V> {
+    impl_map!(body);
+}
+
+/// Parses the entries output by the default [`MapFormat`](crate::MapFormat), splitting them at
+/// the first `=`
+impl<K, V, S> FromArgValue for HashMap<K, V, S>
+where
+    K: FromArgValue + Eq + Hash,
+    V: FromArgValue,
+    S: BuildHasher + Default,
+{
+    impl_map!(body);
+}
+
+// ========== HELPERS ==========
+
+const fn is_variable(arity: Arity) -> bool {
+    matches!(arity, Arity::Optional | Arity::Many | Arity::Repeated)
+}
+
+/// Panic if a field of `arity` output without a name, after the first `before` named fields,
+/// makes the output ambiguous
+const fn check_unnamed(named: &[Arity], drops_name: &[bool], before: usize, arity: Arity) {
+    if !is_variable(arity) {
+        return;
+    }
+
+    let mut idx = 0;
+    while idx < before {
+        if drops_name[idx] {
+            if is_variable(named[idx]) {
+                panic!("`FromArgs` can't tell apart several optional or multi-value fields output without a name");
+            }
+        } else if matches!(named[idx], Arity::Many) {
+            panic!("`FromArgs` can't tell where a multi-value field ends if it's followed by an optional or multi-value field output without a name");
+        }
+        idx += 1;
+    }
+}
+
+/// A `key=value` map entry. Values producing several arguments get joined with a `,` & ones
+/// producing none get output without the `=`.
+fn parse_entry<K: FromArgValue, V: FromArgValue>(entry: &OsStr) -> Result<(K, V), InvalidValue> {
+    let str = entry.to_str().ok_or_else(|| InvalidValue {
+        value: entry.to_owned(),
+        reason: "not valid UTF-8".into(),
+    })?;
+
+    let (key, value) = match str.split_once('=') {
+        Some((key, value)) => (key, Some(value)),
+        None => (str, None),
+    };
+    let key = K::from_values(&[OsStr::new(key)])?;
+    let value = match value {
+        Some(value) if V::ARITY == Arity::Many => {
+            V::from_values(&value.split(',').map(OsStr::new).collect::<Vec<_>>())
+        }
+        Some(value) => V::from_values(&[OsStr::new(value)]),
+        None => V::absent().ok_or_else(|| InvalidValue {
+            value: entry.to_owned(),
+            reason: "expected `key=value`".into(),
+        }),
+    }?;
+
+    Ok((key, value))
+}
+
+fn single<'a>(values: &[&'a OsStr]) -> Result<&'a OsStr, InvalidValue> {
+    match *values {
+        [value] => Ok(value),
+        _ => Err(InvalidValue {
+            value: OsString::new(),
+            reason: format!("expected 1 value, got {}", values.len()),
+        }),
+    }
+}
+
+fn parse<T: FromArgValue>(field: &'static str, values: &[&OsStr]) -> Result<T, FromArgsError> {
+    T::from_values(values).map_err(move |e| FromArgsError::InvalidValue {
+        field,
+        value: e.value,
+        reason: e.reason,
+    })
+}
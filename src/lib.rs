@@ -61,20 +61,69 @@
 //! ]);
 //! ```
 //!
+//! Argument sets that can fail to produce their arguments implement [`TryArg`] instead and get
+//! added via [`ArgConsumer::try_add_arg_set`].
+//!
 //! Support for [`async-std`](async_std) and [`tokio`] can be enabled via their respective features.
 
 #![warn(missing_docs)]
 #![cfg_attr(doc_cfg, feature(doc_cfg))]
 
+#[doc(hidden)]
+pub use arg::AddFormatterOutput;
 pub use arg::Arg;
+#[doc(hidden)]
+pub use arg_consumer::ArgBuffer;
 pub use arg_consumer::{ArgConsumer, CollectedArgs};
+pub use arg_error::{ArgError, ArgErrorKind, ConstraintViolation};
+pub use arg_set_builder::ArgSetBuilder;
+#[cfg(feature = "derive")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "derive")))]
+pub use argley_macro::ArgBuilder;
+pub use diff::{ArgChange, ArgDiff};
+pub use formatted::{FormatArg, Formatted, TryFormatArg};
+#[doc(hidden)]
+pub use from_args::ArgParser;
+pub use from_args::{Arity, FromArgValue, FromArgs, FromArgsError, InvalidValue};
+pub use map_format::MapFormat;
+pub use merge::{Merge, MergeDeep};
+pub use number_format::NumberFormat;
+pub use path_mode::PathMode;
+#[doc(hidden)]
+pub use schema::{ArgProbe, NotArg, NotTryArg, TryArgProbe};
+pub use schema::{ArgSchema, FieldKind, FieldSchema, Schema, SchemaKind, VariantSchema};
+pub use trace::{ArgTracer, TracedArg, TracedArgKind};
+#[doc(hidden)]
+pub use try_arg::{AddViaArg, AddViaTryArg, FieldArg};
+pub use try_arg::{IntoArgResult, IntoTryArg, TryArg};
 
 mod arg;
 mod arg_consumer;
+mod arg_error;
 mod arg_impls;
+mod arg_set_builder;
+mod diff;
+mod formatted;
+mod from_args;
+mod map_format;
+mod merge;
+mod number_format;
+mod path_mode;
+mod schema;
+mod trace;
+mod try_arg;
+mod try_arg_impls;
+mod usage;
+pub mod validate;
 
 #[allow(missing_docs)]
 pub mod prelude {
     pub use crate::Arg;
+    #[cfg(feature = "derive")]
+    pub use crate::ArgBuilder;
     pub use crate::ArgConsumer;
+    pub use crate::ArgSetBuilder;
+    pub use crate::FromArgs;
+    pub use crate::Merge;
+    pub use crate::TryArg;
 }
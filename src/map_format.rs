@@ -0,0 +1,242 @@
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsString;
+
+use crate::arg_consumer::{ArgConsumer, CollectedArgs};
+use crate::formatted::{FormatArg, TryFormatArg};
+use crate::{Arg, ArgError};
+
+/// Format specification for map arguments. Used by the [`Arg`] implementations of
+/// [`HashMap`] & [`BTreeMap`] as well as the derive macro's `kv_separator`, `kv_join` and
+/// `kv_repeat_name` field attributes.
+///
+/// Each entry gets rendered as `key<separator>value`. Entries get output in key order, including
+/// those of a [`HashMap`]. Values that don't produce any arguments, e.g. [`None`], output just the
+/// key; values that produce several, e.g. a `Vec`, get joined with a `,`.
+///
+/// - By default the name gets repeated before every entry, e.g. `-D a=1 -D b=2`
+/// - With [`repeat_name(false)`](Self::repeat_name) it gets output once, e.g. `--set a=1 b=2`
+/// - With [`join`](Self::join) all the entries get joined into a single argument, e.g.
+///   `--env a=1,b=2`
+///
+/// # Example
+///
+/// ```
+/// # use argley::prelude::*;
+/// # use argley::{CollectedArgs, Formatted, MapFormat};
+/// # use std::collections::HashMap;
+/// let map = HashMap::from([("b", 2), ("a", 1)]);
+///
+/// let mut args = CollectedArgs::new();
+/// map.add_to("-D", &mut args);
+/// assert_eq!(&args[..], &["-D", "a=1", "-D", "b=2"]);
+///
+/// let format = MapFormat::new().separator(":").join(",");
+/// let mut args = CollectedArgs::new();
+/// Formatted::new(&map, format).add_to("--labels", &mut args);
+/// assert_eq!(&args[..], &["--labels", "a:1,b:2"]);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MapFormat {
+    separator: &'static str,
+    join: Option<&'static str>,
+    repeat_name: bool,
+}
+
+impl MapFormat {
+    /// Create a format with a `=` separator that repeats the name before every entry.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            separator: "=",
+            join: None,
+            repeat_name: true,
+        }
+    }
+
+    /// String to output between the key and the value
+    #[must_use]
+    pub const fn separator(mut self, separator: &'static str) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Join all the entries into a single argument with this string
+    #[must_use]
+    pub const fn join(mut self, join: &'static str) -> Self {
+        self.join = Some(join);
+        self
+    }
+
+    /// Whether to output the name before every entry or just once. Ignored when
+    /// [joining](Self::join).
+    #[must_use]
+    pub const fn repeat_name(mut self, repeat_name: bool) -> Self {
+        self.repeat_name = repeat_name;
+        self
+    }
+
+    fn add_entries<'a, K, V, I>(
+        &self,
+        entries: I,
+        name: Option<&str>,
+        consumer: &mut impl ArgConsumer,
+    ) -> bool
+    where
+        K: Arg + 'a,
+        V: Arg + 'a,
+        I: IntoIterator<Item = (&'a K, &'a V)>,
+    {
+        let mut entries = entries
+            .into_iter()
+            .filter_map(move |(key, value)| self.render_entry(key, value))
+            .peekable();
+
+        if entries.peek().is_none() {
+            return false;
+        }
+
+        if let Some(join) = self.join {
+            if let Some(name) = name {
+                consumer.add_name(name);
+            }
+            consumer.add_arg(join_args(entries, join));
+        } else {
+            for (idx, entry) in entries.enumerate() {
+                match name {
+                    Some(name) if idx == 0 || self.repeat_name => {
+                        consumer.add_name(name);
+                    }
+                    _ => {}
+                }
+                consumer.add_arg(entry);
+            }
+        }
+
+        true
+    }
+
+    /// `None` if the key doesn't produce any arguments
+    fn render_entry(&self, key: &impl Arg, value: &impl Arg) -> Option<OsString> {
+        let key = key.collect_to::<CollectedArgs>();
+        if key.is_empty() {
+            return None;
+        }
+
+        let mut out = join_args(key, ",");
+        let value = value.collect_to::<CollectedArgs>();
+        if !value.is_empty() {
+            out.push(self.separator);
+            out.push(join_args(value, ","));
+        }
+
+        Some(out)
+    }
+}
+
+impl Default for MapFormat {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Arg + Ord, V: Arg, S> FormatArg<MapFormat> for HashMap<K, V, S> {
+    fn add_formatted_to(
+        &self,
+        format: &MapFormat,
+        name: &str,
+        consumer: &mut impl ArgConsumer,
+    ) -> bool {
+        format.add_entries(sorted(self), Some(name), consumer)
+    }
+
+    fn add_formatted_unnamed_to(
+        &self,
+        format: &MapFormat,
+        consumer: &mut impl ArgConsumer,
+    ) -> bool {
+        format.add_entries(sorted(self), None, consumer)
+    }
+}
+
+impl<K: Arg, V: Arg> FormatArg<MapFormat> for BTreeMap<K, V> {
+    #[inline]
+    fn add_formatted_to(
+        &self,
+        format: &MapFormat,
+        name: &str,
+        consumer: &mut impl ArgConsumer,
+    ) -> bool {
+        format.add_entries(self, Some(name), consumer)
+    }
+
+    #[inline]
+    fn add_formatted_unnamed_to(
+        &self,
+        format: &MapFormat,
+        consumer: &mut impl ArgConsumer,
+    ) -> bool {
+        format.add_entries(self, None, consumer)
+    }
+}
+
+impl<K: Arg + Ord, V: Arg, S> TryFormatArg<MapFormat> for HashMap<K, V, S> {
+    #[inline]
+    fn try_add_formatted_to(
+        &self,
+        format: &MapFormat,
+        name: &str,
+        consumer: &mut impl ArgConsumer,
+    ) -> Result<bool, ArgError> {
+        Ok(self.add_formatted_to(format, name, consumer))
+    }
+
+    #[inline]
+    fn try_add_formatted_unnamed_to(
+        &self,
+        format: &MapFormat,
+        consumer: &mut impl ArgConsumer,
+    ) -> Result<bool, ArgError> {
+        Ok(self.add_formatted_unnamed_to(format, consumer))
+    }
+}
+
+impl<K: Arg, V: Arg> TryFormatArg<MapFormat> for BTreeMap<K, V> {
+    #[inline]
+    fn try_add_formatted_to(
+        &self,
+        format: &MapFormat,
+        name: &str,
+        consumer: &mut impl ArgConsumer,
+    ) -> Result<bool, ArgError> {
+        Ok(self.add_formatted_to(format, name, consumer))
+    }
+
+    #[inline]
+    fn try_add_formatted_unnamed_to(
+        &self,
+        format: &MapFormat,
+        consumer: &mut impl ArgConsumer,
+    ) -> Result<bool, ArgError> {
+        Ok(self.add_formatted_unnamed_to(format, consumer))
+    }
+}
+
+// ========== HELPERS ==========
+
+fn sorted<K: Ord, V, S>(map: &HashMap<K, V, S>) -> Vec<(&K, &V)> {
+    let mut entries = map.iter().collect::<Vec<_>>();
+    entries.sort_unstable_by(move |a, b| a.0.cmp(b.0));
+    entries
+}
+
+fn join_args(args: impl IntoIterator<Item = OsString>, separator: &str) -> OsString {
+    let mut out = OsString::new();
+    for (idx, arg) in args.into_iter().enumerate() {
+        if idx != 0 {
+            out.push(separator);
+        }
+        out.push(arg);
+    }
+    out
+}
@@ -0,0 +1,247 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque};
+use std::ffi::OsString;
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[cfg(feature = "derive")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "derive")))]
+pub use argley_macro::Merge;
+
+/// Types that can be combined with a later layer of themselves, e.g. defaults, then a config
+/// file, then per-call overrides. Implemented by the [`Merge` derive](argley_macro::Merge).
+///
+/// # Example
+///
+/// ```
+/// # use argley::prelude::*;
+/// #[derive(Merge, Default, Debug, PartialEq)]
+/// struct Args {
+///     jobs: Option<u8>,
+///     verbose: bool,
+///     features: Vec<String>,
+///     #[arg(merge = "replace")]
+///     targets: Vec<String>,
+/// }
+///
+/// let mut args = Args {
+///     jobs: Some(4),
+///     features: vec!["a".into()],
+///     targets: vec!["x86".into()],
+///     ..Default::default()
+/// };
+/// args.merge(Args {
+///     verbose: true,
+///     features: vec!["b".into()],
+///     targets: vec!["arm".into()],
+///     ..Default::default()
+/// });
+///
+/// assert_eq!(args, Args {
+///     jobs: Some(4),
+///     verbose: true,
+///     features: vec!["a".into(), "b".into()],
+///     targets: vec!["arm".into()],
+/// });
+/// ```
+pub trait Merge: Sized {
+    /// Merge a later layer into this one
+    fn merge(&mut self, other: Self);
+
+    /// Merge a later layer into this one, replacing collections instead of appending to them.
+    /// Used for fields with `merge = "replace"`; the same as [`merge`](Self::merge) for
+    /// everything but collections.
+    #[inline]
+    fn merge_replace(&mut self, other: Self) {
+        self.merge(other);
+    }
+}
+
+/// Merging of an [`Option`]'s contents. Used for fields with `merge = "deep"`.
+///
+/// # Example
+///
+/// ```
+/// # use argley::prelude::*;
+/// #[derive(Merge, Default, Debug, PartialEq)]
+/// struct Db {
+///     host: Option<String>,
+///     port: Option<u16>,
+/// }
+///
+/// #[derive(Merge, Default, Debug, PartialEq)]
+/// struct Args {
+///     #[arg(merge = "deep")]
+///     db: Option<Db>,
+///     #[arg(merge = "skip")]
+///     layer: u8,
+/// }
+///
+/// let mut args = Args {
+///     db: Some(Db { host: Some("localhost".into()), port: Some(5432) }),
+///     layer: 0,
+/// };
+/// args.merge(Args {
+///     db: Some(Db { host: Some("db".into()), port: None }),
+///     layer: 1,
+/// });
+///
+/// assert_eq!(args, Args {
+///     db: Some(Db { host: Some("db".into()), port: Some(5432) }),
+///     layer: 0,
+/// });
+/// ```
+pub trait MergeDeep {
+    /// Merge a later layer into this one, merging the contents if both are set
+    fn merge_deep(&mut self, other: Self);
+}
+
+// ========== MACROS ==========
+
+macro_rules! impl_override {
+    ($($ty: ty) +) => {
+        $(
+            /// The later value wins
+            impl Merge for $ty {
+                #[inline]
+                fn merge(&mut self, other: Self) {
+                    *self = other;
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! impl_extend {
+    (body) => {
+        #[inline]
+        fn merge(&mut self, other: Self) {
+            self.extend(other);
+        }
+
+        #[inline]
+        fn merge_replace(&mut self, other: Self) {
+            if !other.is_empty() {
+                *self = other;
+            }
+        }
+    };
+    ($($ty: ty) +) => {
+        $(
+            /// Appends the later value's elements
+            impl<T> Merge for $ty {
+                impl_extend!(body);
+            }
+        )+
+    };
+}
+
+// ========== MACRO_CALLS ==========
+
+impl_override!(i8 u8 i16 u16 i32 u32 i64 u64 i128 u128 isize usize f32 f64 char);
+impl_override!(String PathBuf OsString);
+impl_extend!(Vec<T> VecDeque<T> LinkedList<T>);
+
+// ========== CUSTOM_IMPLS ==========
+
+/// Either value being `true` makes the result `true`
+impl Merge for bool {
+    #[inline]
+    fn merge(&mut self, other: Self) {
+        *self |= other;
+    }
+}
+
+/// Overridden by the later value if it's `Some`
+impl<T> Merge for Option<T> {
+    #[inline]
+    fn merge(&mut self, other: Self) {
+        if other.is_some() {
+            *self = other;
+        }
+    }
+}
+
+/// Merges the values if both are `Some`, otherwise the same as [`Merge`]
+impl<T: Merge> MergeDeep for Option<T> {
+    fn merge_deep(&mut self, other: Self) {
+        if let Some(other) = other {
+            match self {
+                Some(this) => this.merge(other),
+                None => *self = Some(other),
+            }
+        }
+    }
+}
+
+/// Appends the later value's elements
+impl<T: Ord> Merge for BTreeSet<T> {
+    impl_extend!(body);
+}
+
+/// Appends the later value's elements
+impl<T: Eq + Hash, S: BuildHasher> Merge for HashSet<T, S> {
+    impl_extend!(body);
+}
+
+/// Adds the later value's entries, overriding existing keys
+impl<K: Ord, V> Merge for BTreeMap<K, V> {
+    impl_extend!(body);
+}
+
+/// Adds the later value's entries, overriding existing keys
+impl<K: Eq + Hash, V, S: BuildHasher> Merge for HashMap<K, V, S> {
+    impl_extend!(body);
+}
+
+impl<T: Merge> Merge for Box<T> {
+    #[inline]
+    fn merge(&mut self, other: Self) {
+        T::merge(self, *other);
+    }
+
+    #[inline]
+    fn merge_replace(&mut self, other: Self) {
+        T::merge_replace(self, *other);
+    }
+}
+
+/// The later value wins
+impl<T: ?Sized> Merge for Rc<T> {
+    #[inline]
+    fn merge(&mut self, other: Self) {
+        *self = other;
+    }
+}
+
+/// The later value wins
+impl<T: ?Sized> Merge for Arc<T> {
+    #[inline]
+    fn merge(&mut self, other: Self) {
+        *self = other;
+    }
+}
+
+/// The later value wins
+impl<'a, T: ?Sized + ToOwned> Merge for Cow<'a, T> {
+    #[inline]
+    fn merge(&mut self, other: Self) {
+        *self = other;
+    }
+}
+
+/// The later value wins
+impl<T: ?Sized> Merge for &T {
+    #[inline]
+    fn merge(&mut self, other: Self) {
+        *self = other;
+    }
+}
+
+impl<T: ?Sized> Merge for PhantomData<T> {
+    #[inline]
+    fn merge(&mut self, _: Self) {}
+}
@@ -0,0 +1,211 @@
+use crate::arg_consumer::ArgConsumer;
+use crate::formatted::{FormatArg, TryFormatArg};
+use crate::ArgError;
+
+/// Format specification for numeric arguments. Used by the derive macro's `precision`, `radix`,
+/// `prefix`, `width` and `pad` field attributes.
+///
+/// - `precision` only applies to floats
+/// - `radix` only applies to integers
+/// - `prefix` gets added after the sign, e.g. `-0x1f`
+/// - `width` is the minimum width of the whole output. When `pad` is `'0'`, the padding goes
+///   between the sign/prefix and the digits, otherwise it goes in front.
+///
+/// # Example
+///
+/// ```
+/// # use argley::prelude::*;
+/// # use argley::{CollectedArgs, Formatted, NumberFormat};
+/// let mask = NumberFormat::new().radix(16).prefix("0x").width(6).pad('0');
+/// let args = Formatted::new(&255_u16, mask).collect_to::<CollectedArgs>();
+/// assert_eq!(&args[..], &["0x00ff"]);
+///
+/// let mode = NumberFormat::new().radix(8).width(4).pad('0');
+/// let args = Formatted::new(&0o755_u32, mode).collect_to::<CollectedArgs>();
+/// assert_eq!(&args[..], &["0755"]);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NumberFormat {
+    precision: Option<usize>,
+    radix: u32,
+    prefix: &'static str,
+    width: usize,
+    pad: char,
+}
+
+impl NumberFormat {
+    /// Create a format with no precision, a radix of 10, no prefix & no padding.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            precision: None,
+            radix: 10,
+            prefix: "",
+            width: 0,
+            pad: ' ',
+        }
+    }
+
+    /// Number of decimal places to output floats with
+    #[must_use]
+    pub const fn precision(mut self, precision: usize) -> Self {
+        self.precision = Some(precision);
+        self
+    }
+
+    /// Radix to output integers in
+    ///
+    /// # Panics
+    /// If the radix is not in the range `2..=36`
+    #[must_use]
+    pub const fn radix(mut self, radix: u32) -> Self {
+        assert!(
+            radix >= 2 && radix <= 36,
+            "radix must be in the range 2..=36"
+        );
+        self.radix = radix;
+        self
+    }
+
+    /// String to output between the sign and the digits
+    #[must_use]
+    pub const fn prefix(mut self, prefix: &'static str) -> Self {
+        self.prefix = prefix;
+        self
+    }
+
+    /// Minimum width of the output
+    #[must_use]
+    pub const fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Character to pad the output with when it's shorter than the [width](Self::width)
+    #[must_use]
+    pub const fn pad(mut self, pad: char) -> Self {
+        self.pad = pad;
+        self
+    }
+
+    fn render(&self, negative: bool, digits: &str) -> String {
+        let sign = if negative { "-" } else { "" };
+        let len = sign.len() + self.prefix.chars().count() + digits.chars().count();
+        let padding = self.width.saturating_sub(len);
+
+        let mut out = String::with_capacity(len + padding);
+        if self.pad == '0' {
+            out.push_str(sign);
+            out.push_str(self.prefix);
+            out.extend(std::iter::repeat(self.pad).take(padding));
+        } else {
+            out.extend(std::iter::repeat(self.pad).take(padding));
+            out.push_str(sign);
+            out.push_str(self.prefix);
+        }
+        out.push_str(digits);
+
+        out
+    }
+}
+
+impl Default for NumberFormat {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ========== MACROS ==========
+
+macro_rules! impl_int {
+    (body => |$value: ident| $negative: expr, $abs: expr) => {
+        fn add_formatted_unnamed_to(&self, format: &NumberFormat, consumer: &mut impl ArgConsumer) -> bool {
+            let $value = *self;
+            #[allow(clippy::unnecessary_cast)]
+            let digits = to_radix($abs as u128, format.radix);
+            consumer.add_arg(format.render($negative, &digits));
+            true
+        }
+    };
+    (unsigned => $($ty: ty) +) => {
+        $(
+            impl FormatArg<NumberFormat> for $ty {
+                impl_int!(body => |value| false, value);
+            }
+        )+
+    };
+    (signed => $($ty: ty) +) => {
+        $(
+            impl FormatArg<NumberFormat> for $ty {
+                impl_int!(body => |value| value < 0, value.unsigned_abs());
+            }
+        )+
+    };
+}
+
+macro_rules! impl_float {
+    ($($ty: ty) +) => {
+        $(
+            impl FormatArg<NumberFormat> for $ty {
+                fn add_formatted_unnamed_to(&self, format: &NumberFormat, consumer: &mut impl ArgConsumer) -> bool {
+                    let abs = self.abs();
+                    let digits = if let Some(precision) = format.precision {
+                        format!("{abs:.precision$}")
+                    } else {
+                        abs.to_string()
+                    };
+                    consumer.add_arg(format.render(self.is_sign_negative() && !self.is_nan(), &digits));
+                    true
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! impl_via_format {
+    ($($ty: ty) +) => {
+        $(
+            impl TryFormatArg<NumberFormat> for $ty {
+                #[inline]
+                fn try_add_formatted_to(&self, format: &NumberFormat, name: &str, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+                    Ok(FormatArg::add_formatted_to(self, format, name, consumer))
+                }
+
+                #[inline]
+                fn try_add_formatted_unnamed_to(&self, format: &NumberFormat, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+                    Ok(FormatArg::add_formatted_unnamed_to(self, format, consumer))
+                }
+            }
+        )+
+    };
+}
+
+// ========== MACRO_CALLS ==========
+
+impl_int!(unsigned => u8 u16 u32 u64 u128 usize);
+impl_int!(signed => i8 i16 i32 i64 i128 isize);
+impl_float!(f32 f64);
+impl_via_format!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize f32 f64);
+
+// ========== HELPERS ==========
+
+fn to_radix(mut value: u128, radix: u32) -> String {
+    if radix == 10 {
+        return value.to_string();
+    }
+
+    let radix = u128::from(radix);
+    let mut digits = Vec::new();
+    loop {
+        #[allow(clippy::cast_possible_truncation)]
+        let digit = (value % radix) as u32;
+        digits.push(std::char::from_digit(digit, 36).unwrap_or('?'));
+        value /= radix;
+        if value == 0 {
+            break;
+        }
+    }
+
+    digits.iter().rev().collect()
+}
@@ -0,0 +1,216 @@
+use std::borrow::Cow;
+use std::ffi::OsStr;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use crate::arg_consumer::ArgConsumer;
+use crate::formatted::TryFormatArg;
+use crate::{ArgError, ArgErrorKind};
+
+/// How to normalise a path argument before passing it on. Used by the derive macro's `path`
+/// field attribute.
+///
+/// Every mode expands a leading `~` to the current user's home directory first. Paths that fail to
+/// resolve produce an [`ArgError`], so paths only implement
+/// [`TryFormatArg`](crate::TryFormatArg) of it & a [`Formatted`](crate::Formatted) path only
+/// implements [`TryArg`](crate::TryArg).
+///
+/// # Example
+///
+/// ```
+/// # use argley::prelude::*;
+/// # use argley::{CollectedArgs, Formatted, PathMode};
+/// # use std::path::Path;
+/// let path = Path::new("/srv/data/input.txt");
+/// let mode = PathMode::relative_to("/srv/work");
+///
+/// let args = Formatted::new(path, mode).try_collect_to::<CollectedArgs>().unwrap();
+/// assert_eq!(&args[..], &[Path::new("../data/input.txt")]);
+/// ```
+///
+/// There is no infallible way of adding a formatted path:
+///
+/// ```compile_fail
+/// # use argley::prelude::*;
+/// # use argley::{CollectedArgs, Formatted, PathMode};
+/// # use std::path::Path;
+/// let args = Formatted::new(Path::new("~/data"), PathMode::ExpandHome).collect_to::<CollectedArgs>();
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PathMode<'a> {
+    /// Only expand `~`
+    ExpandHome,
+
+    /// Resolve relative paths against the current working directory
+    Absolute,
+
+    /// Resolve the path via [`std::fs::canonicalize`]. The path must exist.
+    Canonical,
+
+    /// Express the path relative to the given base directory, e.g. a child process'
+    /// `current_dir`. Relative paths & bases get resolved against the current working directory
+    /// first.
+    RelativeTo(&'a Path),
+}
+
+impl<'a> PathMode<'a> {
+    /// Shorthand for [`PathMode::RelativeTo`]
+    #[inline]
+    pub fn relative_to(base: &'a (impl AsRef<Path> + ?Sized)) -> Self {
+        Self::RelativeTo(base.as_ref())
+    }
+
+    /// Normalise the given path according to this mode.
+    ///
+    /// # Errors
+    ///
+    /// - The path starts with `~` and the home directory can't be determined
+    /// - The current working directory can't be determined
+    /// - [`Canonical`](PathMode::Canonical) was used and the path doesn't exist
+    /// - [`RelativeTo`](PathMode::RelativeTo) was used and there is no relative path between
+    ///   the path & the base, e.g. they're on different drives
+    pub fn resolve<'p>(&self, path: &'p Path) -> io::Result<Cow<'p, Path>> {
+        let path = expand_home(path)?;
+
+        Ok(match *self {
+            Self::ExpandHome => path,
+            Self::Absolute => Cow::Owned(absolute(&path)?),
+            Self::Canonical => Cow::Owned(path.canonicalize()?),
+            Self::RelativeTo(base) => Cow::Owned(relative_to(&path, &expand_home(base)?)?),
+        })
+    }
+}
+
+// ========== MACROS ==========
+
+macro_rules! impl_path {
+    ($($ty: ty) +) => {
+        $(
+            impl TryFormatArg<PathMode<'_>> for $ty {
+                fn try_add_formatted_to(&self, format: &PathMode<'_>, name: &str, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+                    let path = resolve_for_arg(format, self.as_ref())?;
+                    consumer.add_name(name).add_arg(path.as_os_str());
+                    Ok(true)
+                }
+
+                fn try_add_formatted_unnamed_to(&self, format: &PathMode<'_>, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+                    let path = resolve_for_arg(format, self.as_ref())?;
+                    consumer.add_arg(path.as_os_str());
+                    Ok(true)
+                }
+            }
+        )+
+    };
+}
+
+// ========== MACRO_CALLS ==========
+
+impl_path!(Path PathBuf);
+
+// ========== HELPERS ==========
+
+fn resolve_for_arg<'p>(mode: &PathMode<'_>, path: &'p Path) -> Result<Cow<'p, Path>, ArgError> {
+    mode.resolve(path).map_err(move |source| {
+        ArgError::new(ArgErrorKind::Path {
+            path: path.to_owned(),
+            source,
+        })
+    })
+}
+
+fn expand_home(path: &Path) -> io::Result<Cow<'_, Path>> {
+    let mut components = path.components();
+    match components.next() {
+        Some(Component::Normal(first)) if first == OsStr::new("~") => {
+            let home = home_dir().ok_or_else(move || {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "unable to determine the home directory",
+                )
+            })?;
+
+            Ok(Cow::Owned(home.join(components.as_path())))
+        }
+        _ => Ok(Cow::Borrowed(path)),
+    }
+}
+
+fn home_dir() -> Option<PathBuf> {
+    let var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+
+    match std::env::var_os(var) {
+        Some(home) if !home.is_empty() => Some(PathBuf::from(home)),
+        _ => None,
+    }
+}
+
+/// Resolve the path against the current working directory & normalise `.` and `..` lexically
+fn absolute(path: &Path) -> io::Result<PathBuf> {
+    let path = if path.is_absolute() {
+        Cow::Borrowed(path)
+    } else {
+        Cow::Owned(std::env::current_dir()?.join(path))
+    };
+
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                // `..` at the root is the root
+                if let Some(Component::Normal(_)) = out.components().next_back() {
+                    out.pop();
+                }
+            }
+            component => out.push(component),
+        }
+    }
+
+    Ok(out)
+}
+
+fn relative_to(path: &Path, base: &Path) -> io::Result<PathBuf> {
+    let path = absolute(path)?;
+    let base = absolute(base)?;
+
+    let mut path_components = path.components().peekable();
+    let mut base_components = base.components().peekable();
+
+    // Windows drive prefixes must match for there to be a relative path
+    if let (Some(Component::Prefix(a)), Some(Component::Prefix(b))) =
+        (path_components.peek(), base_components.peek())
+    {
+        if a != b {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "no relative path from {} to {}",
+                    base.display(),
+                    path.display()
+                ),
+            ));
+        }
+    }
+
+    while let (Some(a), Some(b)) = (path_components.peek(), base_components.peek()) {
+        if a != b {
+            break;
+        }
+        path_components.next();
+        base_components.next();
+    }
+
+    let mut out = PathBuf::new();
+    for component in base_components {
+        if component != Component::CurDir {
+            out.push(Component::ParentDir);
+        }
+    }
+    out.extend(path_components);
+
+    if out.as_os_str().is_empty() {
+        out.push(Component::CurDir);
+    }
+
+    Ok(out)
+}
@@ -0,0 +1,229 @@
+use std::marker::PhantomData;
+
+use crate::{Arg, TryArg};
+
+/// Types that can describe the arguments they produce. Implemented by the
+/// [derive macro](argley_macro::Arg) for containers marked with `#[arg(schema)]`.
+///
+/// # Example
+///
+/// ```
+/// # use argley::prelude::*;
+/// # use argley::{ArgSchema, FieldKind, SchemaKind};
+/// #[derive(Arg)]
+/// #[arg(schema)]
+/// struct Args {
+///     /// Compress the output
+///     compress: bool,
+///     #[arg(rename = "dir")]
+///     output_dir: String,
+///     #[arg(variadic)]
+///     inputs: Vec<String>,
+/// }
+///
+/// let fields = match Args::schema().kind {
+///     SchemaKind::Struct(fields) => fields,
+///     _ => unreachable!(),
+/// };
+///
+/// assert_eq!(fields[0].name, Some("--compress"));
+/// assert_eq!(fields[0].kind, FieldKind::Flag);
+/// assert_eq!(fields[0].doc, Some("Compress the output"));
+/// assert_eq!(fields[1].name, Some("--dir"));
+/// assert_eq!(fields[1].type_name, "String");
+/// assert_eq!(fields[2].kind, FieldKind::Variadic);
+///
+/// assert_eq!(Args::names(), [("compress", "--compress"), ("output_dir", "--dir")]);
+/// ```
+pub trait ArgSchema {
+    /// Description of the type's arguments
+    fn schema() -> &'static Schema;
+
+    /// `(field, emitted name)` pairs of the fields that get output with a name, e.g.
+    /// `("output_dir", "--dir")`, so that code outside the type doesn't need to repeat the
+    /// string literals. Enum variants' names are keyed by the variant & their fields' by
+    /// `Variant.field`. Fields whose type ignores the name, e.g. a `drop_name`, `subcommand` or
+    /// `flag_enum` type, are left out as their name never gets output.
+    fn names() -> Vec<(&'static str, &'static str)>;
+}
+
+/// Description of a type's arguments
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Schema {
+    /// The type's name
+    pub name: &'static str,
+
+    /// The type's doc comment
+    pub doc: Option<&'static str>,
+
+    /// The type's structure
+    pub kind: SchemaKind,
+}
+
+impl Schema {
+    #[doc(hidden)]
+    pub const fn new(name: &'static str, doc: Option<&'static str>, kind: SchemaKind) -> Self {
+        Self { name, doc, kind }
+    }
+}
+
+/// Structure of a [`Schema`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SchemaKind {
+    /// A struct with the given fields. Skipped fields are omitted; the rest are in declaration
+    /// order.
+    Struct(&'static [FieldSchema]),
+
+    /// An enum with the given variants. Skipped variants are omitted; the rest are in declaration
+    /// order.
+    Enum(&'static [VariantSchema]),
+
+    /// A type output as a single value, e.g. via `to_string` or `as_repr`
+    Value,
+}
+
+/// Description of a struct or enum variant field
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FieldSchema {
+    /// The field's name in the source, or its index for tuple fields
+    pub label: &'static str,
+
+    /// The name the field gets output with, e.g. `--output-dir`; `None` for fields output
+    /// without one, including those whose type ignores the name
+    pub name: Option<&'static str>,
+
+    /// How the field gets output
+    pub kind: FieldKind,
+
+    /// The field's position, if it's [`Positional`](FieldKind::Positional)
+    pub position: Option<u16>,
+
+    /// The field's type as written in the source
+    pub type_name: &'static str,
+
+    /// Whether the field may be absent from the output, i.e. isn't `required` & its type's
+    /// [`Arg::OPTIONAL`] is set, as it is for flags, `Option`s & collections. Fields whose type
+    /// isn't known to implement [`Arg`], e.g. generic ones bound by something else, aren't
+    /// optional.
+    pub optional: bool,
+
+    /// Placeholder for the field's value set via `value_name`; see
+    /// [`value_placeholder`](Self::value_placeholder)
+    pub value_name: Option<&'static str>,
+
+    /// The field's doc comment
+    pub doc: Option<&'static str>,
+}
+
+impl FieldSchema {
+    #[doc(hidden)]
+    #[allow(clippy::too_many_arguments)]
+    pub const fn new(
+        label: &'static str,
+        name: Option<&'static str>,
+        kind: FieldKind,
+        position: Option<u16>,
+        type_name: &'static str,
+        optional: bool,
+        value_name: Option<&'static str>,
+        doc: Option<&'static str>,
+    ) -> Self {
+        Self {
+            label,
+            name,
+            kind,
+            position,
+            type_name,
+            optional,
+            value_name,
+            doc,
+        }
+    }
+
+    /// `name` unless the field's type drops it. A function rather than an `if` in the generated
+    /// code as branches stop the schema's slices from being promoted to `'static`s.
+    #[doc(hidden)]
+    pub const fn kept_name(name: &'static str, drops_name: bool) -> Option<&'static str> {
+        if drops_name {
+            None
+        } else {
+            Some(name)
+        }
+    }
+}
+
+/// How a field gets output
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FieldKind {
+    /// A `bool` output as just its name
+    Flag,
+
+    /// A value output after its name
+    Option,
+
+    /// A value output without a name at a fixed position
+    Positional,
+
+    /// A value output without a name after all the other fields
+    Variadic,
+
+    /// A nested argument set output without a name, i.e. a `subcommand` field. Only set for
+    /// fields marked as `subcommand`: the derive can't see other fields' types, so fields holding
+    /// other derived types get described by how they're declared, e.g. as
+    /// [`Option`](Self::Option)s. Use the nested type's own [`ArgSchema`] to describe them.
+    Nested,
+}
+
+/// Description of an enum variant
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct VariantSchema {
+    /// The variant's name in the source
+    pub label: &'static str,
+
+    /// The subcommand or flag name the variant gets output with, if any
+    pub name: Option<&'static str>,
+
+    /// The variant's fields
+    pub fields: &'static [FieldSchema],
+
+    /// The variant's doc comment
+    pub doc: Option<&'static str>,
+}
+
+/// Reads the [`Arg`] consts of types that implement it, falling back to [`NotArg`]'s for the
+/// rest
+#[doc(hidden)]
+pub struct ArgProbe<T: ?Sized>(PhantomData<T>);
+
+impl<T: ?Sized + Arg> ArgProbe<T> {
+    pub const OPTIONAL: bool = T::OPTIONAL;
+    pub const DROPS_NAME: bool = T::DROPS_NAME;
+}
+
+#[doc(hidden)]
+pub trait NotArg {
+    const OPTIONAL: bool = false;
+    const DROPS_NAME: bool = false;
+}
+
+impl<T: ?Sized> NotArg for ArgProbe<T> {}
+
+/// [`ArgProbe`] for [`TryArg`]
+#[doc(hidden)]
+pub struct TryArgProbe<T: ?Sized>(PhantomData<T>);
+
+impl<T: ?Sized + TryArg> TryArgProbe<T> {
+    pub const OPTIONAL: bool = T::OPTIONAL;
+    pub const DROPS_NAME: bool = T::DROPS_NAME;
+}
+
+#[doc(hidden)]
+pub trait NotTryArg {
+    const OPTIONAL: bool = false;
+    const DROPS_NAME: bool = false;
+}
+
+impl<T: ?Sized> NotTryArg for TryArgProbe<T> {}
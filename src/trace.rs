@@ -0,0 +1,205 @@
+use std::ffi::{OsStr, OsString};
+use std::fmt::{Display, Formatter};
+
+use crate::{Arg, ArgConsumer, ArgError, ArgSchema, TryArg};
+
+/// Consumer recording which field produced each argument. Relies on the field & variant
+/// boundaries reported by the [derive macro](argley_macro::Arg), so arguments of hand-written
+/// implementations get attributed to the innermost derived field containing them. Names are
+/// the arguments added through [`ArgConsumer::add_name`].
+///
+/// # Example
+///
+/// ```
+/// # use argley::prelude::*;
+/// # use argley::{ArgTracer, TracedArg, TracedArgKind};
+/// #[derive(Arg)]
+/// #[arg(drop_name)]
+/// struct Db {
+///     host: String,
+/// }
+///
+/// #[derive(Arg)]
+/// #[arg(static_args = ["serve"], schema)]
+/// struct Outer {
+///     db: Db,
+/// }
+///
+/// let tracer = ArgTracer::of(&Outer { db: Db { host: "localhost".into() } });
+///
+/// assert_eq!(tracer.args(), &[
+///     TracedArg { arg: "serve".into(), path: "Outer".into(), kind: TracedArgKind::Static },
+///     TracedArg { arg: "--host".into(), path: "Outer.db.host".into(), kind: TracedArgKind::Name },
+///     TracedArg { arg: "localhost".into(), path: "Outer.db.host".into(), kind: TracedArgKind::Value },
+/// ]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ArgTracer {
+    root: String,
+    scopes: Vec<String>,
+    args: Vec<TracedArg>,
+}
+
+/// An argument recorded by an [`ArgTracer`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TracedArg {
+    /// The argument
+    pub arg: OsString,
+
+    /// Path of the field that produced it, e.g. `Outer.db.host` or `Mode::Named.c`, starting
+    /// with the tracer's root
+    pub path: String,
+
+    /// What the argument is
+    pub kind: TracedArgKind,
+}
+
+/// What a [`TracedArg`] is
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TracedArgKind {
+    /// The name of a field, subcommand or flag variant
+    Name,
+
+    /// A value
+    Value,
+
+    /// One of a container's or variant's `static_args`
+    Static,
+}
+
+impl ArgTracer {
+    /// Create a tracer whose paths start with `root`, e.g. the name of the type being traced
+    pub fn new(root: impl Into<String>) -> Self {
+        Self {
+            root: root.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Trace the arguments of a value, using its [schema](ArgSchema)'s name as the root. Derived
+    /// types need `#[arg(schema)]`; use [`new`](Self::new) to name the root yourself otherwise.
+    pub fn of<T: Arg + ArgSchema + ?Sized>(value: &T) -> Self {
+        let mut tracer = Self::new(T::schema().name);
+        value.add_unnamed_to(&mut tracer);
+        tracer
+    }
+
+    /// [`of`](Self::of) for [`TryArg`] implementations
+    ///
+    /// # Errors
+    ///
+    /// Propagated from [`TryArg::try_add_unnamed_to`]
+    pub fn try_of<T: TryArg + ArgSchema + ?Sized>(value: &T) -> Result<Self, ArgError> {
+        let mut tracer = Self::new(T::schema().name);
+        value.try_add_unnamed_to(&mut tracer)?;
+        Ok(tracer)
+    }
+
+    /// The recorded arguments in the order they were added
+    #[inline]
+    pub fn args(&self) -> &[TracedArg] {
+        &self.args
+    }
+
+    /// Take the recorded arguments
+    #[inline]
+    pub fn into_args(self) -> Vec<TracedArg> {
+        self.args
+    }
+
+    fn path(&self) -> &str {
+        match self.scopes.last() {
+            Some(path) => path,
+            None => &self.root,
+        }
+    }
+
+    fn enter(&mut self, separator: &str, segment: &str) {
+        let path = match self.path() {
+            "" => segment.to_owned(),
+            parent => format!("{parent}{separator}{segment}"),
+        };
+        self.scopes.push(path);
+    }
+
+    fn record(&mut self, arg: &OsStr, kind: TracedArgKind) {
+        self.args.push(TracedArg {
+            arg: arg.to_owned(),
+            path: self.path().to_owned(),
+            kind,
+        });
+    }
+}
+
+impl ArgConsumer for ArgTracer {
+    fn add_arg(&mut self, arg: impl AsRef<OsStr>) -> &mut Self {
+        self.record(arg.as_ref(), TracedArgKind::Value);
+        self
+    }
+
+    fn add_args(&mut self, args: impl IntoIterator<Item = impl AsRef<OsStr>>) -> &mut Self {
+        for arg in args {
+            self.record(arg.as_ref(), TracedArgKind::Value);
+        }
+        self
+    }
+
+    fn add_name(&mut self, name: impl AsRef<OsStr>) -> &mut Self {
+        self.record(name.as_ref(), TracedArgKind::Name);
+        self
+    }
+
+    fn add_static_args(&mut self, args: impl IntoIterator<Item = impl AsRef<OsStr>>) -> &mut Self {
+        for arg in args {
+            self.record(arg.as_ref(), TracedArgKind::Static);
+        }
+        self
+    }
+
+    #[inline]
+    fn enter_field(&mut self, field: &'static str, _: Option<&'static str>) {
+        self.enter(".", field);
+    }
+
+    #[inline]
+    fn exit_field(&mut self) {
+        self.scopes.pop();
+    }
+
+    #[inline]
+    fn enter_variant(&mut self, variant: &'static str, _: Option<&'static str>) {
+        self.enter("::", variant);
+    }
+
+    #[inline]
+    fn exit_variant(&mut self) {
+        self.scopes.pop();
+    }
+}
+
+impl Display for TracedArgKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match *self {
+            Self::Name => "name",
+            Self::Value => "value",
+            Self::Static => "static",
+        })
+    }
+}
+
+impl Display for TracedArg {
+    /// `"arg" <- path (kind)`
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} <- {} ({})", self.arg, self.path, self.kind)
+    }
+}
+
+impl Display for ArgTracer {
+    /// A line per argument
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for arg in &self.args {
+            writeln!(f, "{arg}")?;
+        }
+        Ok(())
+    }
+}
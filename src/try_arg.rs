@@ -0,0 +1,172 @@
+use crate::{Arg, ArgConsumer, ArgError};
+
+/// A fallible counterpart of [`Arg`](crate::Arg). Derived for every type that derives `Arg`;
+/// types whose derive can fail, i.e. ones with `#[arg(fallible)]`, only get this trait.
+///
+/// Implemented for all the standard types [`Arg`](crate::Arg) is implemented for. Fields of
+/// fallible derives whose type only implements `Arg`, e.g. through a handwritten
+/// implementation, get added through `Arg` instead.
+///
+/// # Example
+///
+/// ```
+/// # use argley::prelude::*;
+/// # use argley::{ArgError, CollectedArgs};
+/// #[derive(Arg)]
+/// #[arg(fallible)]
+/// struct Args {
+///     #[arg(formatter = parse_level)]
+///     level: &'static str,
+/// }
+///
+/// fn parse_level(level: &&'static str) -> Result<u8, ArgError> {
+///     level.parse().map_err(|_| ArgError::custom(format!("invalid level: {level}")))
+/// }
+///
+/// let mut command = std::process::Command::new("foo");
+///
+/// assert!(command.try_add_arg_set(&Args { level: "3" }).is_ok());
+///
+/// let error = command.try_add_arg_set(&Args { level: "x" }).unwrap_err();
+/// assert_eq!(error.to_string(), "`level`: invalid level: x");
+///
+/// let args = command.get_args().collect::<Vec<_>>();
+/// assert_eq!(&args[..], &["--level", "3"]);
+/// ```
+pub trait TryArg {
+    /// Fallible counterpart of [`Arg::OPTIONAL`](crate::Arg::OPTIONAL)
+    const OPTIONAL: bool = false;
+
+    /// Fallible counterpart of [`Arg::DROPS_NAME`](crate::Arg::DROPS_NAME)
+    const DROPS_NAME: bool = false;
+
+    /// Fallible counterpart of [`Arg::add_to`](crate::Arg::add_to)
+    ///
+    /// # Errors
+    ///
+    /// Implementation-specific
+    fn try_add_to(&self, name: &str, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+        consumer.add_name(name);
+        self.try_add_unnamed_to(consumer)
+    }
+
+    /// Fallible counterpart of [`Arg::add_unnamed_to`](crate::Arg::add_unnamed_to)
+    ///
+    /// # Errors
+    ///
+    /// Implementation-specific
+    fn try_add_unnamed_to(&self, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError>;
+
+    /// Fallible counterpart of [`Arg::collect_to`](crate::Arg::collect_to)
+    ///
+    /// # Errors
+    ///
+    /// Propagated from [`try_add_unnamed_to`](TryArg::try_add_unnamed_to)
+    fn try_collect_to<C: Default + ArgConsumer>(&self) -> Result<C, ArgError> {
+        let mut consumer = C::default();
+        self.try_add_unnamed_to(&mut consumer)?;
+        Ok(consumer)
+    }
+}
+
+/// Conversion of a formatter's return value into a [`TryArg`]. Lets formatters of fallible
+/// derives return either `impl TryArg` or `Result<impl TryArg, impl Into<ArgError>>`.
+pub trait IntoTryArg {
+    /// The resulting argument
+    type Output: TryArg;
+
+    /// Perform the conversion
+    ///
+    /// # Errors
+    ///
+    /// If `self` is an [`Err`]
+    fn into_try_arg(self) -> Result<Self::Output, ArgError>;
+}
+
+impl<T: TryArg> IntoTryArg for T {
+    type Output = T;
+
+    #[inline]
+    fn into_try_arg(self) -> Result<Self::Output, ArgError> {
+        Ok(self)
+    }
+}
+
+impl<T: TryArg, E: Into<ArgError>> IntoTryArg for Result<T, E> {
+    type Output = T;
+
+    #[inline]
+    fn into_try_arg(self) -> Result<Self::Output, ArgError> {
+        self.map_err(Into::into)
+    }
+}
+
+/// Conversion of the return value of a `with` module's functions into the result of
+/// [`TryArg::try_add_to`]. Lets the functions of fallible derives return either `bool` or
+/// `Result<bool, impl Into<ArgError>>`.
+pub trait IntoArgResult {
+    /// Perform the conversion
+    ///
+    /// # Errors
+    ///
+    /// If `self` is an [`Err`]
+    fn into_arg_result(self) -> Result<bool, ArgError>;
+}
+
+impl IntoArgResult for bool {
+    #[inline]
+    fn into_arg_result(self) -> Result<bool, ArgError> {
+        Ok(self)
+    }
+}
+
+impl<E: Into<ArgError>> IntoArgResult for Result<bool, E> {
+    #[inline]
+    fn into_arg_result(self) -> Result<bool, ArgError> {
+        self.map_err(Into::into)
+    }
+}
+
+/// A field of a fallible derive. Adding it goes through [`TryArg`] if the field's type
+/// implements it & [`Arg`] otherwise, as method resolution tries [`AddViaTryArg`] on
+/// `FieldArg` before [`AddViaArg`] on `&FieldArg`.
+#[doc(hidden)]
+pub struct FieldArg<'a, T: ?Sized>(pub &'a T);
+
+#[doc(hidden)]
+pub trait AddViaTryArg {
+    fn add_field_to(&self, name: &str, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError>;
+
+    fn add_field_unnamed_to(&self, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError>;
+}
+
+impl<T: ?Sized + TryArg> AddViaTryArg for FieldArg<'_, T> {
+    #[inline]
+    fn add_field_to(&self, name: &str, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+        self.0.try_add_to(name, consumer)
+    }
+
+    #[inline]
+    fn add_field_unnamed_to(&self, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+        self.0.try_add_unnamed_to(consumer)
+    }
+}
+
+#[doc(hidden)]
+pub trait AddViaArg {
+    fn add_field_to(&self, name: &str, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError>;
+
+    fn add_field_unnamed_to(&self, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError>;
+}
+
+impl<T: ?Sized + Arg> AddViaArg for &FieldArg<'_, T> {
+    #[inline]
+    fn add_field_to(&self, name: &str, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+        Ok(self.0.add_to(name, consumer))
+    }
+
+    #[inline]
+    fn add_field_unnamed_to(&self, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+        Ok(self.0.add_unnamed_to(consumer))
+    }
+}
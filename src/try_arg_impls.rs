@@ -0,0 +1,167 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque};
+use std::ffi::{OsStr, OsString};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::arg_consumer::ArgConsumer;
+use crate::{Arg, ArgError, TryArg};
+
+// ========== MACROS ==========
+
+macro_rules! impl_via_arg {
+    (body) => {
+        const OPTIONAL: bool = <Self as Arg>::OPTIONAL;
+        const DROPS_NAME: bool = <Self as Arg>::DROPS_NAME;
+
+        #[inline]
+        fn try_add_to(&self, name: &str, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+            Ok(Arg::add_to(self, name, consumer))
+        }
+
+        #[inline]
+        fn try_add_unnamed_to(&self, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+            Ok(Arg::add_unnamed_to(self, consumer))
+        }
+    };
+    ($($ty: ty) +) => {
+        $(
+            impl TryArg for $ty {
+                impl_via_arg!(body);
+            }
+        )+
+    };
+}
+
+macro_rules! impl_deref {
+    (body) => {
+        const OPTIONAL: bool = T::OPTIONAL;
+        const DROPS_NAME: bool = T::DROPS_NAME;
+
+        #[inline]
+        fn try_add_to(&self, name: &str, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+            TryArg::try_add_to(&**self, name, consumer)
+        }
+
+        #[inline]
+        fn try_add_unnamed_to(&self, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+            TryArg::try_add_unnamed_to(&**self, consumer)
+        }
+    };
+    ($($ty: ty) +) => {
+        $(
+            impl<T: ?Sized + TryArg> TryArg for $ty {
+                impl_deref!(body);
+            }
+        )+
+    };
+}
+
+macro_rules! impl_iter {
+    (body) => {
+        const OPTIONAL: bool = true;
+
+        fn try_add_to(&self, name: &str, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+            if self.is_empty() {
+                Ok(false)
+            } else {
+                consumer.add_name(name);
+                process_iter(self, consumer)
+            }
+        }
+
+        fn try_add_unnamed_to(&self, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+            if self.is_empty() {
+                Ok(false)
+            } else {
+                process_iter(self, consumer)
+            }
+        }
+    };
+    ($($ty: ty) +) => {
+        $(
+            impl<T: TryArg> TryArg for $ty {
+                impl_iter!(body);
+            }
+        )+
+    };
+}
+
+// ========== MACRO_CALLS ==========
+
+impl_via_arg!(i8 u8 i16 u16 i32 u32 i64 u64 i128 u128 isize usize f32 f64 char bool);
+impl_via_arg!(String str PathBuf Path OsString OsStr);
+impl_deref!(&T Box<T> Rc<T> Arc<T>);
+impl_iter!([T] Vec<T> BTreeSet<T> LinkedList<T> VecDeque<T>);
+
+// ========== CUSTOM_IMPLS ==========
+
+impl<T: TryArg, const N: usize> TryArg for [T; N] {
+    const OPTIONAL: bool = N == 0;
+
+    #[inline]
+    fn try_add_to(&self, name: &str, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+        self[..].try_add_to(name, consumer)
+    }
+
+    #[inline]
+    fn try_add_unnamed_to(&self, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+        self[..].try_add_unnamed_to(consumer)
+    }
+}
+
+impl<T: TryArg, S> TryArg for HashSet<T, S> {
+    impl_iter!(body);
+}
+
+impl<'a, T: ?Sized + TryArg + ToOwned + 'a> TryArg for Cow<'a, T> {
+    impl_deref!(body);
+}
+
+impl<K: Arg + Ord, V: Arg, S> TryArg for HashMap<K, V, S> {
+    impl_via_arg!(body);
+}
+
+impl<K: Arg, V: Arg> TryArg for BTreeMap<K, V> {
+    impl_via_arg!(body);
+}
+
+impl<T: ?Sized> TryArg for PhantomData<T> {
+    impl_via_arg!(body);
+}
+
+impl<T: TryArg> TryArg for Option<T> {
+    const OPTIONAL: bool = true;
+    const DROPS_NAME: bool = T::DROPS_NAME;
+
+    fn try_add_to(&self, name: &str, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+        if let Some(value) = self {
+            value.try_add_to(name, consumer)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn try_add_unnamed_to(&self, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError> {
+        if let Some(value) = self {
+            value.try_add_unnamed_to(consumer)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+// ========== HELPERS ==========
+
+fn process_iter<'a, E, I>(iter: I, consumer: &mut impl ArgConsumer) -> Result<bool, ArgError>
+where
+    E: TryArg + 'a,
+    I: IntoIterator<Item = &'a E>,
+{
+    for element in iter {
+        element.try_add_unnamed_to(consumer)?;
+    }
+    Ok(true)
+}
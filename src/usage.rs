@@ -0,0 +1,192 @@
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use crate::schema::{FieldKind, FieldSchema, Schema, SchemaKind};
+
+impl Schema {
+    /// Render a usage synopsis, e.g. `prog [--compress] --dir <DIR> [<INPUT_FILES>...]`.
+    ///
+    /// Named fields come first in declaration order, followed by positional fields by position,
+    /// the variadic field & finally the subcommand. [Optional](FieldSchema::optional) fields get
+    /// wrapped in `[]`. Enums render a line per variant, starting with the variant's name if it
+    /// has one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argley::prelude::*;
+    /// # use argley::ArgSchema;
+    /// #[derive(Arg)]
+    /// #[arg(schema)]
+    /// struct Args {
+    ///     compress: bool,
+    ///     #[arg(value_name = "DIR")]
+    ///     dir: String,
+    ///     level: Option<u8>,
+    ///     #[arg(variadic)]
+    ///     input_files: Vec<String>,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     Args::schema().usage("prog"),
+    ///     "prog [--compress] --dir <DIR> [--level <LEVEL>] [<INPUT_FILES>...]"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn usage(&self, program: &str) -> String {
+        match self.kind {
+            SchemaKind::Struct(fields) => usage_line(program, None, fields),
+            SchemaKind::Enum(variants) => {
+                let lines = variants
+                    .iter()
+                    .map(move |v| usage_line(program, v.name, v.fields))
+                    .collect::<Vec<_>>();
+                lines.join("\n")
+            }
+            SchemaKind::Value => format!("{program} <{}>", screaming_snake(self.name)),
+        }
+    }
+
+    /// Render a markdown table of the fields with their synopses, types & doc comments. Enums
+    /// render a table per variant with fields, each preceded by the variant's name & doc comment.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argley::prelude::*;
+    /// # use argley::ArgSchema;
+    /// #[derive(Arg)]
+    /// #[arg(schema)]
+    /// struct Args {
+    ///     /// Compress the output
+    ///     compress: bool,
+    ///     /// Where to write the output
+    ///     #[arg(value_name = "DIR")]
+    ///     dir: String,
+    /// }
+    ///
+    /// assert_eq!(Args::schema().markdown(), "\
+    /// | Option | Type | Description |
+    /// |---|---|---|
+    /// | `--compress` | `bool` | Compress the output |
+    /// | `--dir <DIR>` | `String` | Where to write the output |
+    /// ");
+    /// ```
+    #[must_use]
+    pub fn markdown(&self) -> String {
+        let mut out = String::new();
+
+        match self.kind {
+            SchemaKind::Struct(fields) => markdown_table(fields, &mut out),
+            SchemaKind::Enum(variants) => {
+                for variant in variants.iter().filter(move |v| !v.fields.is_empty()) {
+                    if !out.is_empty() {
+                        out.push('\n');
+                    }
+
+                    let _ = write!(out, "**`{}`**", variant.name.unwrap_or(variant.label));
+                    if let Some(doc) = variant.doc {
+                        let _ = write!(out, ": {}", markdown_cell(doc));
+                    }
+                    out.push_str("\n\n");
+                    markdown_table(variant.fields, &mut out);
+                }
+            }
+            SchemaKind::Value => {}
+        }
+
+        out
+    }
+}
+
+impl FieldSchema {
+    /// The field's `value_name` or, if it doesn't have one, its label in `SCREAMING_SNAKE_CASE`
+    #[must_use]
+    pub fn value_placeholder(&self) -> Cow<'static, str> {
+        match self.value_name {
+            Some(name) => Cow::Borrowed(name),
+            None => Cow::Owned(screaming_snake(self.label)),
+        }
+    }
+
+    /// How the field appears in a usage line without the `[]` optional fields get wrapped in,
+    /// e.g. `--dir <DIR>`
+    #[must_use]
+    pub fn synopsis(&self) -> String {
+        let placeholder = self.value_placeholder();
+        match self.kind {
+            FieldKind::Flag => self.name.unwrap_or_default().to_owned(),
+            FieldKind::Option => match self.name {
+                Some(name) => format!("{name} <{placeholder}>"),
+                None => format!("<{placeholder}>"),
+            },
+            FieldKind::Variadic => format!("<{placeholder}>..."),
+            FieldKind::Positional | FieldKind::Nested => format!("<{placeholder}>"),
+        }
+    }
+}
+
+// ========== HELPERS ==========
+
+fn usage_line(program: &str, name: Option<&str>, fields: &[FieldSchema]) -> String {
+    let mut out = String::from(program);
+    if let Some(name) = name {
+        out.push(' ');
+        out.push_str(name);
+    }
+
+    for field in usage_order(fields) {
+        out.push(' ');
+        if field.optional {
+            let _ = write!(out, "[{}]", field.synopsis());
+        } else {
+            out.push_str(&field.synopsis());
+        }
+    }
+
+    out
+}
+
+fn usage_order(fields: &[FieldSchema]) -> Vec<&FieldSchema> {
+    let mut fields = fields.iter().collect::<Vec<_>>();
+    fields.sort_by_key(move |f| match f.kind {
+        FieldKind::Flag | FieldKind::Option => (0, 0),
+        FieldKind::Positional => (1, f.position.unwrap_or_default()),
+        FieldKind::Variadic => (2, 0),
+        FieldKind::Nested => (3, 0),
+    });
+    fields
+}
+
+fn markdown_table(fields: &[FieldSchema], out: &mut String) {
+    out.push_str("| Option | Type | Description |\n|---|---|---|\n");
+    for field in usage_order(fields) {
+        let _ = writeln!(
+            out,
+            "| `{}` | `{}` | {} |",
+            field.synopsis(),
+            field.type_name,
+            field.doc.map(markdown_cell).unwrap_or_default(),
+        );
+    }
+}
+
+/// Fit a doc comment in a table cell: paragraphs get separated by `<br>` & pipes escaped
+fn markdown_cell(doc: &str) -> String {
+    doc.split("\n\n")
+        .map(move |paragraph| paragraph.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join("<br>")
+        .replace('|', "\\|")
+}
+
+fn screaming_snake(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for (idx, ch) in name.char_indices() {
+        if idx != 0 && ch.is_uppercase() && !out.ends_with('_') {
+            out.push('_');
+        }
+        out.push(ch.to_ascii_uppercase());
+    }
+    out
+}
@@ -0,0 +1,337 @@
+//! Validators backing the derive macro's `range`, `non_empty`, `one_of`, `max_len` and `exists`
+//! field attributes. Each returns an [`ArgErrorKind::Invalid`] error naming the offending value.
+//!
+//! ```
+//! # use argley::prelude::*;
+//! # use argley::{ArgErrorKind, CollectedArgs};
+//! #[derive(Arg)]
+//! #[arg(fallible)]
+//! struct Args {
+//!     #[arg(range = 1..=65535)]
+//!     port: u32,
+//!
+//!     #[arg(one_of = ["fast", "slow"])]
+//!     mode: Option<String>,
+//! }
+//!
+//! let args = Args { port: 0, mode: None };
+//! let error = args.try_collect_to::<CollectedArgs>().unwrap_err();
+//!
+//! assert_eq!(error.to_string(), "`port`: invalid value 0: expected a value in the range 1..=65535");
+//! ```
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque};
+use std::ffi::{OsStr, OsString};
+use std::fmt::Debug;
+use std::ops::RangeBounds;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::{ArgError, ArgErrorKind};
+
+/// Visits the individual values of an argument, e.g. each element of a `Vec` or the content of a
+/// `Some`. Values that aren't present, such as [`None`], don't get visited.
+pub trait ArgValues {
+    /// The type of the visited values
+    type Value: ?Sized;
+
+    /// Call `f` with each value, stopping at the first error.
+    ///
+    /// # Errors
+    ///
+    /// Propagated from `f`
+    fn for_each_value(
+        &self,
+        f: &mut dyn FnMut(&Self::Value) -> Result<(), ArgError>,
+    ) -> Result<(), ArgError>;
+}
+
+/// Length of an argument as seen by the `non_empty` & `max_len` validators: the number of
+/// characters in a string, the number of bytes in an [`OsStr`] or [`Path`] and the number of
+/// elements in a collection or entries in a map.
+pub trait ArgLen {
+    /// The length, or `None` if the value isn't present, e.g. [`None`]
+    fn arg_len(&self) -> Option<usize>;
+}
+
+/// Check that every value is within the given range
+///
+/// # Errors
+///
+/// On the first value outside the range
+pub fn range<A, R>(arg: &A, range: R) -> Result<(), ArgError>
+where
+    A: ?Sized + ArgValues,
+    A::Value: PartialOrd + Debug + Sized,
+    R: RangeBounds<A::Value> + Debug,
+{
+    arg.for_each_value(&mut |value| {
+        if range.contains(value) {
+            Ok(())
+        } else {
+            Err(invalid(
+                value,
+                format!("expected a value in the range {range:?}"),
+            ))
+        }
+    })
+}
+
+/// Check that every value satisfies `predicate`. `options` is the human-readable list of allowed
+/// values used in the error message.
+///
+/// # Errors
+///
+/// On the first value not satisfying the predicate
+pub fn one_of<A, P>(arg: &A, options: &'static str, predicate: P) -> Result<(), ArgError>
+where
+    A: ?Sized + ArgValues,
+    A::Value: Debug,
+    P: Fn(&A::Value) -> bool,
+{
+    arg.for_each_value(&mut |value| {
+        if predicate(value) {
+            Ok(())
+        } else {
+            Err(invalid(value, format!("expected one of {options}")))
+        }
+    })
+}
+
+/// Check that every value is a path that exists
+///
+/// # Errors
+///
+/// On the first path that doesn't exist
+pub fn exists<A>(arg: &A) -> Result<(), ArgError>
+where
+    A: ?Sized + ArgValues,
+    A::Value: AsRef<Path> + Debug,
+{
+    arg.for_each_value(&mut |value| {
+        if value.as_ref().exists() {
+            Ok(())
+        } else {
+            Err(invalid(value, Cow::Borrowed("path does not exist")))
+        }
+    })
+}
+
+/// Check that the argument, if present, isn't empty
+///
+/// # Errors
+///
+/// If the argument is empty
+pub fn non_empty<A>(arg: &A) -> Result<(), ArgError>
+where
+    A: ?Sized + ArgLen + Debug,
+{
+    match arg.arg_len() {
+        Some(0) => Err(invalid(arg, Cow::Borrowed("expected a non-empty value"))),
+        _ => Ok(()),
+    }
+}
+
+/// Check that the argument, if present, is at most `max` long
+///
+/// # Errors
+///
+/// If the argument is too long
+pub fn max_len<A>(arg: &A, max: usize) -> Result<(), ArgError>
+where
+    A: ?Sized + ArgLen + Debug,
+{
+    match arg.arg_len() {
+        Some(len) if len > max => Err(invalid(
+            arg,
+            format!("expected a length of at most {max}, got {len}"),
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn invalid(value: &(impl ?Sized + Debug), reason: impl Into<Cow<'static, str>>) -> ArgError {
+    ArgError::new(ArgErrorKind::Invalid {
+        value: format!("{value:?}"),
+        reason: reason.into(),
+    })
+}
+
+// ========== MACROS ==========
+
+macro_rules! impl_leaf {
+    ($([$ty: ty => $value: ty]) +) => {
+        $(
+            impl ArgValues for $ty {
+                type Value = $value;
+
+                #[inline]
+                fn for_each_value(&self, f: &mut dyn FnMut(&Self::Value) -> Result<(), ArgError>) -> Result<(), ArgError> {
+                    f(self)
+                }
+            }
+        )+
+    };
+    ($($ty: ty) +) => {
+        impl_leaf!($([$ty => $ty]) +);
+    };
+}
+
+macro_rules! impl_deref {
+    ($($ty: ty) +) => {
+        $(
+            impl<T: ?Sized + ArgValues> ArgValues for $ty {
+                type Value = T::Value;
+
+                #[inline]
+                fn for_each_value(&self, f: &mut dyn FnMut(&Self::Value) -> Result<(), ArgError>) -> Result<(), ArgError> {
+                    T::for_each_value(&**self, f)
+                }
+            }
+
+            impl<T: ?Sized + ArgLen> ArgLen for $ty {
+                #[inline]
+                fn arg_len(&self) -> Option<usize> {
+                    T::arg_len(&**self)
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! impl_iter {
+    (body) => {
+        type Value = T::Value;
+
+        fn for_each_value(&self, f: &mut dyn FnMut(&Self::Value) -> Result<(), ArgError>) -> Result<(), ArgError> {
+            for value in self {
+                value.for_each_value(f)?;
+            }
+            Ok(())
+        }
+    };
+    ($($ty: ty) +) => {
+        $(
+            impl<T: ArgValues> ArgValues for $ty {
+                impl_iter!(body);
+            }
+
+            impl<T> ArgLen for $ty {
+                #[inline]
+                fn arg_len(&self) -> Option<usize> {
+                    Some(self.len())
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! impl_len {
+    ($($ty: ty => |$value: ident| $len: expr) +) => {
+        $(
+            impl ArgLen for $ty {
+                #[inline]
+                fn arg_len(&self) -> Option<usize> {
+                    let $value = self;
+                    Some($len)
+                }
+            }
+        )+
+    };
+}
+
+// ========== MACRO_CALLS ==========
+
+impl_leaf!(i8 u8 i16 u16 i32 u32 i64 u64 i128 u128 isize usize f32 f64 bool str Path OsStr);
+impl_leaf!([String => str] [PathBuf => Path] [OsString => OsStr]);
+impl_deref!(&T Box<T> Rc<T> Arc<T>);
+impl_iter!([T] Vec<T> BTreeSet<T> LinkedList<T> VecDeque<T>);
+impl_len! {
+    str => |value| value.chars().count()
+    String => |value| value.chars().count()
+    OsStr => |value| value.len()
+    OsString => |value| value.len()
+    Path => |value| value.as_os_str().len()
+    PathBuf => |value| value.as_os_str().len()
+}
+
+// ========== CUSTOM_IMPLS ==========
+
+impl<T: ArgValues, const N: usize> ArgValues for [T; N] {
+    impl_iter!(body);
+}
+
+impl<T, const N: usize> ArgLen for [T; N] {
+    #[inline]
+    fn arg_len(&self) -> Option<usize> {
+        Some(N)
+    }
+}
+
+impl<T: ArgValues, S> ArgValues for HashSet<T, S> {
+    impl_iter!(body);
+}
+
+impl<T, S> ArgLen for HashSet<T, S> {
+    #[inline]
+    fn arg_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<K, V, S> ArgLen for HashMap<K, V, S> {
+    #[inline]
+    fn arg_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<K, V> ArgLen for BTreeMap<K, V> {
+    #[inline]
+    fn arg_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<'a, T: ?Sized + ArgValues + ToOwned + 'a> ArgValues for Cow<'a, T> {
+    type Value = T::Value;
+
+    #[inline]
+    fn for_each_value(
+        &self,
+        f: &mut dyn FnMut(&Self::Value) -> Result<(), ArgError>,
+    ) -> Result<(), ArgError> {
+        T::for_each_value(self, f)
+    }
+}
+
+impl<'a, T: ?Sized + ArgLen + ToOwned + 'a> ArgLen for Cow<'a, T> {
+    #[inline]
+    fn arg_len(&self) -> Option<usize> {
+        T::arg_len(self)
+    }
+}
+
+impl<T: ArgValues> ArgValues for Option<T> {
+    type Value = T::Value;
+
+    #[inline]
+    fn for_each_value(
+        &self,
+        f: &mut dyn FnMut(&Self::Value) -> Result<(), ArgError>,
+    ) -> Result<(), ArgError> {
+        match self {
+            Some(value) => value.for_each_value(f),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: ArgLen> ArgLen for Option<T> {
+    #[inline]
+    fn arg_len(&self) -> Option<usize> {
+        self.as_ref().and_then(ArgLen::arg_len)
+    }
+}